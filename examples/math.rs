@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use ascolt::{Actor, ActorTrait, CommandMessage, ask_handler, tell_handler};
+use ascolt::{Actor, ActorTrait, CommandMessage, StopInfo, ask_handler, tell_handler};
 use ascolt::{
     error::{
         actor::{ActorInitFailure, ActorStopFailure},
@@ -57,8 +57,11 @@ impl ActorTrait<DefaultHandlerError> for CalcActor {
         Ok(())
     }
 
-    async fn on_stop(&mut self) -> Result<(), ActorStopFailure> {
-        println!("Calc actor stopped");
+    async fn on_stop(&mut self, info: StopInfo) -> Result<(), ActorStopFailure> {
+        println!(
+            "Calc actor stopped ({:?}, {} discarded)",
+            info.mode, info.discarded
+        );
 
         Ok(())
     }
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use ascolt::{
+    ActorTrait, RestartStrategy, Supervisor,
+    error::{actor::ActorInitFailure, handler::DefaultHandlerError},
+    match_messages,
+    runtime::SpawnFuture,
+    supervision::CommandMessage,
+    tell_handler,
+};
+use async_trait::async_trait;
+use thiserror::Error;
+
+pub struct WorkerActor {
+    id: usize,
+}
+
+pub struct DoWorkRequest;
+pub struct CrashRequest;
+
+match_messages! {
+    actor: WorkerActor;
+    error: DefaultHandlerError;
+
+    WorkerActorMessage {
+        DoWorkRequest;
+        CrashRequest;
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Worker crashed")]
+struct WorkerCrashedError;
+
+#[async_trait]
+impl ActorTrait<DefaultHandlerError> for WorkerActor {
+    async fn init(&mut self) -> Result<(), ActorInitFailure> {
+        println!("Worker {} started", self.id);
+
+        Ok(())
+    }
+}
+
+#[tell_handler]
+async fn handle(self: &mut WorkerActor, msg: DoWorkRequest) -> Result<(), DefaultHandlerError> {
+    println!("Worker {} is working", self.id);
+
+    Ok(())
+}
+
+// handler errors escalate to the supervisor by default
+#[tell_handler]
+async fn handle(self: &mut WorkerActor, msg: CrashRequest) -> Result<(), DefaultHandlerError> {
+    Err(WorkerCrashedError.into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (first_tx, first_rx) = ascolt::bounded_channel::<WorkerActorMessage>(100);
+    let (second_tx, second_rx) = ascolt::bounded_channel::<WorkerActorMessage>(100);
+
+    let supervisor = Supervisor::new(RestartStrategy::OneForAll)
+        .with_intensity(3, Duration::from_secs(5))
+        .child(|| WorkerActor { id: 1 }, first_rx)
+        .child(|| WorkerActor { id: 2 }, second_rx);
+
+    let supervisor_handle = tokio::spawn(supervisor.run(|future: SpawnFuture| {
+        tokio::spawn(future);
+    }));
+
+    first_tx.tell(DoWorkRequest).await?;
+    first_tx.tell(CrashRequest).await?; // both workers are restarted
+
+    // commands overtake queued messages, give the crash and restart time to happen first
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    second_tx.tell(DoWorkRequest).await?;
+
+    first_tx.command(CommandMessage::DrainAndStopActor).await?;
+    second_tx.command(CommandMessage::DrainAndStopActor).await?;
+
+    supervisor_handle.await??;
+
+    Ok(())
+}
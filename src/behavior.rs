@@ -0,0 +1,101 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::messaging::MessageVariantTrait;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Accept,
+    Stash,
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+pub struct Behavior<M> {
+    rules: BehaviorRules,
+    marker: PhantomData<fn() -> M>,
+}
+
+// variant names are enough to filter messages, so the actor keeps the stack untyped
+#[derive(Debug, Clone)]
+pub(crate) struct BehaviorRules {
+    name: &'static str,
+    variants: HashMap<&'static str, Admission>,
+    otherwise: Admission,
+}
+
+impl<M> Behavior<M> {
+    pub fn new(name: &'static str) -> Self {
+        Behavior {
+            rules: BehaviorRules {
+                name,
+                variants: HashMap::new(),
+                otherwise: Admission::Accept,
+            },
+            marker: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.rules.name
+    }
+
+    pub fn accept<R>(self) -> Self
+    where
+        R: MessageVariantTrait<M>,
+    {
+        self.admit::<R>(Admission::Accept)
+    }
+
+    pub fn stash<R>(self) -> Self
+    where
+        R: MessageVariantTrait<M>,
+    {
+        self.admit::<R>(Admission::Stash)
+    }
+
+    pub fn reject<R>(self) -> Self
+    where
+        R: MessageVariantTrait<M>,
+    {
+        self.admit::<R>(Admission::Reject)
+    }
+
+    pub fn otherwise(mut self, admission: Admission) -> Self {
+        self.rules.otherwise = admission;
+
+        self
+    }
+
+    pub fn admission<R>(&self) -> Admission
+    where
+        R: MessageVariantTrait<M>,
+    {
+        self.rules.admission(R::variant())
+    }
+
+    fn admit<R>(mut self, admission: Admission) -> Self
+    where
+        R: MessageVariantTrait<M>,
+    {
+        self.rules.variants.insert(R::variant(), admission);
+
+        self
+    }
+
+    pub(crate) fn into_rules(self) -> BehaviorRules {
+        self.rules
+    }
+}
+
+impl BehaviorRules {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn admission(&self, variant: &str) -> Admission {
+        self.variants
+            .get(variant)
+            .copied()
+            .unwrap_or(self.otherwise)
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt::{Debug, Display},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    behavior::Behavior,
+    error::handler::{StashError, StashFullError, Stashed},
+    handler::ActorMessageHandlerTrait,
+    log,
+    messaging::{ActorShared, MessageEnumTrait, Receiver, Sender, WeakSender},
+    runtime::Spawner,
+    spawn::{self, ActorRef},
+    stash::{self, SharedStash},
+    supervision::{ActorMessage, ActorTrait, CommandMessage},
+    watch::{self, ActorId},
+};
+
+type AnyContext = Arc<dyn Any + Send + Sync>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<AnyContext>> = const { RefCell::new(None) };
+}
+
+pub struct ActorContext<M> {
+    shared: Arc<ActorShared>,
+    myself: WeakSender<M>,
+    sent_at: Instant,
+    ttl: Option<Duration>,
+    span: log::Span,
+    requested: Arc<Mutex<Option<CommandMessage>>>,
+    stash: SharedStash<M>,
+    stashed: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+}
+
+impl<M> ActorContext<M>
+where
+    M: Send + 'static,
+{
+    pub(crate) fn new(
+        receiver: &Receiver<M>,
+        stash: SharedStash<M>,
+        sent_at: Instant,
+        ttl: Option<Duration>,
+        span: log::Span,
+    ) -> Self {
+        ActorContext {
+            shared: receiver.shared.clone(),
+            myself: receiver.myself(),
+            sent_at,
+            ttl,
+            span,
+            requested: Arc::new(Mutex::new(None)),
+            stash,
+            stashed: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // only set while the actor is polling a message handler
+    pub fn current() -> Option<Self> {
+        CURRENT.with_borrow(|context| context.as_ref()?.downcast_ref::<ActorContext<M>>().cloned())
+    }
+
+    pub fn id(&self) -> ActorId {
+        self.shared.id
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.shared.name()
+    }
+
+    pub fn myself(&self) -> Option<Sender<M>> {
+        self.myself.upgrade()
+    }
+
+    pub fn sent_at(&self) -> Instant {
+        self.sent_at
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    // applied by the actor loop once the handler returns, after an ask has been replied to
+    pub fn command(&self, command: CommandMessage) {
+        *self.requested.lock().unwrap_or_else(|err| err.into_inner()) = Some(command);
+    }
+
+    pub fn stop(&self) {
+        self.command(CommandMessage::StopActor);
+    }
+
+    pub fn force_stop(&self) {
+        self.command(CommandMessage::ForceStopActor);
+    }
+
+    pub fn restart(&self) {
+        self.command(CommandMessage::RestartActor);
+    }
+
+    // the handler hands its message value back and returns the sentinel, e.g. `Err(stashed.into())`
+    pub fn stash<I>(&self, value: I) -> Result<Stashed, StashError<I>>
+    where
+        I: Send + 'static,
+    {
+        let stash = self.stash.lock();
+        let mut stashed = self.stashed.lock().unwrap_or_else(|err| err.into_inner());
+
+        if stashed.is_some() {
+            return Err(StashError::AlreadyStashed(value));
+        }
+
+        if stash.is_full() {
+            return Err(StashFullError {
+                value,
+                capacity: stash.capacity(),
+            }
+            .into());
+        }
+
+        *stashed = Some(Box::new(value));
+
+        Ok(Stashed)
+    }
+
+    pub fn unstash_all(&self) -> usize {
+        self.stash.lock().unstash_all()
+    }
+
+    pub fn stashed(&self) -> usize {
+        self.stash.lock().len()
+    }
+
+    // switching behavior gives stashed messages another chance under the new one
+    pub fn become_behavior(&self, behavior: Behavior<M>) {
+        self.shared.behaviors().push(behavior.into_rules());
+        self.unstash_all();
+    }
+
+    pub fn replace_behavior(&self, behavior: Behavior<M>) {
+        let mut behaviors = self.shared.behaviors();
+
+        behaviors.pop();
+        behaviors.push(behavior.into_rules());
+        drop(behaviors);
+
+        self.unstash_all();
+    }
+
+    pub fn unbecome(&self) -> bool {
+        let popped = self.shared.behaviors().pop().is_some();
+
+        if popped {
+            self.unstash_all();
+        }
+
+        popped
+    }
+
+    pub fn behavior(&self) -> Option<&'static str> {
+        self.shared
+            .behaviors()
+            .last()
+            .map(|behavior| behavior.name())
+    }
+
+    pub(crate) fn take_requested(&self) -> Option<CommandMessage> {
+        self.requested
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+    }
+
+    pub fn spawn<S, A, N, E>(
+        &self,
+        spawner: &S,
+        actor: A,
+        channel: (Sender<N>, Receiver<N>),
+    ) -> ActorRef<N>
+    where
+        S: Spawner,
+        N: MessageEnumTrait + Send + Sync + 'static,
+        A: ActorMessageHandlerTrait<N, E> + ActorTrait<E> + Send + Sync + 'static,
+        E: Send + Debug + Display + 'static,
+    {
+        let child = spawn::spawn(spawner, actor, channel);
+
+        watch::add_child(&self.shared, child.sender().downgrade());
+
+        child
+    }
+}
+
+impl<M> ActorContext<M>
+where
+    M: MessageEnumTrait + Send + 'static,
+{
+    fn restash<I>(&self, wrap: impl FnOnce(I) -> M) -> bool
+    where
+        I: 'static,
+    {
+        let Some(value) = self
+            .stashed
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+        else {
+            return false;
+        };
+
+        let Ok(value) = value.downcast::<I>() else {
+            log::error("Stashed value does not match the handled message".to_string());
+
+            return false;
+        };
+
+        let pushed = self.stash.lock().push(ActorMessage {
+            msg: wrap(*value),
+            sent_at: self.sent_at,
+            ttl: self.ttl,
+            span: self.span.clone(),
+        });
+
+        if let Err(ActorMessage { msg, .. }) = pushed {
+            stash::overflow(&self.shared, msg);
+        }
+
+        true
+    }
+}
+
+impl<M> Clone for ActorContext<M> {
+    fn clone(&self) -> ActorContext<M> {
+        ActorContext {
+            shared: self.shared.clone(),
+            myself: self.myself.clone(),
+            sent_at: self.sent_at,
+            ttl: self.ttl,
+            span: self.span.clone(),
+            requested: self.requested.clone(),
+            stash: self.stash.clone(),
+            stashed: self.stashed.clone(),
+        }
+    }
+}
+
+// called by the message handler once the actor handler returned
+pub(crate) fn restash<M, I>(wrap: impl FnOnce(I) -> M) -> bool
+where
+    M: MessageEnumTrait + Send + 'static,
+    I: 'static,
+{
+    CURRENT.with_borrow(|context| {
+        context
+            .as_ref()
+            .and_then(|context| context.downcast_ref::<ActorContext<M>>())
+            .is_some_and(|context| context.restash(wrap))
+    })
+}
+
+pub(crate) struct Scoped<F> {
+    future: F,
+    context: AnyContext,
+}
+
+pub(crate) fn scope<F, M>(context: ActorContext<M>, future: F) -> Scoped<F>
+where
+    M: Send + 'static,
+{
+    Scoped {
+        future,
+        context: Arc::new(context),
+    }
+}
+
+struct Restore(Option<AnyContext>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.set(self.0.take());
+    }
+}
+
+impl<F> Future for Scoped<F>
+where
+    F: Future + Unpin,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // restored on drop so a panicking handler does not leak its context
+        let _restore = Restore(CURRENT.replace(Some(self.context.clone())));
+
+        Pin::new(&mut self.future).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel,
+        context::ActorContext,
+        error::handler::{DefaultHandlerError, StashError},
+        handler::AskHandlerTrait,
+        runtime::SpawnFuture,
+        spawn,
+        supervision::ActorTrait,
+    };
+
+    struct HoldingActor {
+        holding: bool,
+        refused: bool,
+    }
+
+    #[derive(Debug)]
+    pub struct Hold;
+    pub struct Release;
+
+    crate::match_messages! {
+        actor: HoldingActor;
+        error: DefaultHandlerError;
+
+        HoldingActorMessage {
+            Hold -> ();
+            Release -> bool;
+        }
+    }
+
+    impl ActorTrait<DefaultHandlerError> for HoldingActor {}
+
+    #[async_trait]
+    impl AskHandlerTrait<Hold, (), DefaultHandlerError> for HoldingActor {
+        async fn handle(&mut self, msg: Hold) -> Result<(), DefaultHandlerError> {
+            if !self.holding {
+                return Ok(());
+            }
+
+            let context = ActorContext::<HoldingActorMessage>::current().unwrap();
+            let stashed = context.stash(msg)?;
+
+            self.refused = matches!(context.stash(Hold), Err(StashError::AlreadyStashed(_)));
+
+            Err(stashed.into())
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Release, bool, DefaultHandlerError> for HoldingActor {
+        async fn handle(&mut self, _msg: Release) -> Result<bool, DefaultHandlerError> {
+            self.holding = false;
+            ActorContext::<HoldingActorMessage>::current()
+                .unwrap()
+                .unstash_all();
+
+            Ok(self.refused)
+        }
+    }
+
+    #[tokio::test]
+    async fn handler_stashes_only_once() {
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        let actor_ref = spawn(
+            &spawner,
+            HoldingActor {
+                holding: true,
+                refused: false,
+            },
+            bounded_channel(10),
+        );
+
+        let held = actor_ref.ask(Hold);
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            actor_ref.ask(Release).await
+        };
+        let (held, refused) = tokio::join!(held, release);
+
+        assert!(refused.unwrap());
+        assert!(held.is_ok());
+    }
+}
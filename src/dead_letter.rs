@@ -0,0 +1,116 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{
+    log,
+    messaging::{self, ActorShared, MessageRequest, MessageSender, TellMessage},
+    watch::ActorId,
+};
+
+static GLOBAL_SINK: OnceLock<Mutex<Option<Arc<dyn DeadLetterSinkTrait>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadLetterReason {
+    Expired,
+    MailboxClosed,
+    Overflow,
+    Stopped,
+    Rejected,
+}
+
+impl fmt::Display for DeadLetterReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeadLetterReason::Expired => write!(f, "expired"),
+            DeadLetterReason::MailboxClosed => write!(f, "mailbox closed"),
+            DeadLetterReason::Overflow => write!(f, "mailbox overflow"),
+            DeadLetterReason::Stopped => write!(f, "actor stopped"),
+            DeadLetterReason::Rejected => write!(f, "rejected by behavior"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub target: ActorId,
+    pub target_name: Option<String>,
+    pub variant: &'static str,
+    pub reason: DeadLetterReason,
+}
+
+impl fmt::Display for DeadLetter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target_name {
+            Some(name) => write!(f, "{} to {name} ({})", self.variant, self.target)?,
+            None => write!(f, "{} to {}", self.variant, self.target)?,
+        }
+
+        write!(f, ": {}", self.reason)
+    }
+}
+
+pub(crate) trait DeadLetterSinkTrait: Send + Sync {
+    fn deliver(&self, dead_letter: DeadLetter);
+}
+
+impl<W> DeadLetterSinkTrait for MessageSender<W>
+where
+    W: Send + Sync + 'static,
+    TellMessage<DeadLetter>: MessageRequest<W>,
+{
+    // bypasses dead letter reporting, a closed sink must not report its own dead letters
+    fn deliver(&self, dead_letter: DeadLetter) {
+        let msg = messaging::tell_message(dead_letter, None);
+        let priority = <TellMessage<DeadLetter> as MessageRequest<W>>::priority();
+
+        if let Err(err) = self.try_send_message(msg, priority) {
+            log::error(format!("Dead letter lost: {err}"));
+        }
+    }
+}
+
+fn global_sink() -> &'static Mutex<Option<Arc<dyn DeadLetterSinkTrait>>> {
+    GLOBAL_SINK.get_or_init(|| Mutex::new(None))
+}
+
+pub fn set_global_sink<W>(sink: impl Into<MessageSender<W>>)
+where
+    W: Send + Sync + 'static,
+    TellMessage<DeadLetter>: MessageRequest<W>,
+{
+    *global_sink().lock().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(sink.into()));
+}
+
+pub fn clear_global_sink() {
+    *global_sink().lock().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+pub(crate) fn report(shared: &ActorShared, variant: &'static str, reason: DeadLetterReason) {
+    let dead_letter = DeadLetter {
+        target: shared.id,
+        target_name: shared.name(),
+        variant,
+        reason,
+    };
+
+    log::info(format!("Dead letter: {dead_letter}"));
+
+    // the sink is cloned out so delivering never happens under a lock
+    let sink = shared
+        .dead_letters
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+        .or_else(|| {
+            global_sink()
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone()
+        });
+
+    if let Some(sink) = sink {
+        sink.deliver(dead_letter);
+    }
+}
@@ -1,9 +1,11 @@
 use async_channel::RecvError;
 use derive_more::From;
-use std::fmt::{self, Debug};
+use std::{
+    any::Any,
+    fmt::{self, Debug},
+};
 use thiserror::Error;
 
-#[doc(hidden)]
 #[derive(Error, Debug, From)]
 pub enum ActorRuntimeError {
     #[error("Init error: {0}")]
@@ -14,6 +16,43 @@ pub enum ActorRuntimeError {
     Stop(ActorStopFailure),
     #[error("Error while processing handler error: {0}")]
     HandleError(ActorHandleErrorFailure),
+    #[error("{0}")]
+    Panic(ActorPanic),
+    #[error("Actor escalated a failure")]
+    Escalated,
+    #[from(skip)]
+    #[error(
+        "Restart policy exhausted after {attempts} attempts{}",
+        .init.as_ref().map(|error| format!(": {error}")).unwrap_or_default()
+    )]
+    RestartExhausted {
+        attempts: usize,
+        init: Option<ActorInitFailure>,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("Actor task was dropped before it finished")]
+pub struct ActorJoinError;
+
+#[derive(Error, Debug)]
+#[error("Actor panicked: {message}")]
+pub struct ActorPanic {
+    pub message: String,
+}
+
+impl ActorPanic {
+    pub(crate) fn from_payload(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown panic payload".to_string(),
+            },
+        };
+
+        ActorPanic { message }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -1,5 +1,8 @@
 use derive_more::From;
-use std::fmt::{self, Debug};
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -16,12 +19,137 @@ impl ReceiverClosedError {
 #[error("Receiver handle error")]
 pub struct ReceiverHandlerError;
 
+#[derive(Error, Debug)]
+pub enum OpaqueHandlerError {}
+
 #[derive(Error, Debug, From)]
-pub enum AskError {
+pub enum AskError<E = OpaqueHandlerError> {
     #[error("{0}")]
     ReceiverClosed(ReceiverClosedError),
     #[error("{0}")]
     ReceiverHandlerError(ReceiverHandlerError),
+    #[from(skip)]
+    #[error("Handler error: {0}")]
+    Handler(E),
+    #[from(skip)]
+    #[error("Receiver handler panicked")]
+    HandlerPanicked,
+    #[from(skip)]
+    #[error("Ask timed out")]
+    Timeout,
+    #[from(skip)]
+    #[error("Ask expired before it was handled")]
+    Expired,
+    #[from(skip)]
+    #[error("Actor stopped before the ask was handled")]
+    ActorStopping,
+    #[from(skip)]
+    #[error("Ask dropped because the mailbox is full")]
+    MailboxFull,
+    #[from(skip)]
+    #[error("Ask rejected by the {0} behavior")]
+    Rejected(&'static str),
+}
+
+impl AskError {
+    pub(crate) fn into_typed<E>(self) -> AskError<E> {
+        match self {
+            AskError::ReceiverClosed(error) => AskError::ReceiverClosed(error),
+            AskError::ReceiverHandlerError(error) => AskError::ReceiverHandlerError(error),
+            AskError::Handler(error) => match error {},
+            AskError::HandlerPanicked => AskError::HandlerPanicked,
+            AskError::Timeout => AskError::Timeout,
+            AskError::Expired => AskError::Expired,
+            AskError::ActorStopping => AskError::ActorStopping,
+            AskError::MailboxFull => AskError::MailboxFull,
+            AskError::Rejected(behavior) => AskError::Rejected(behavior),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Mailbox is full")]
+pub struct MailboxFullError<I>(pub I);
+
+impl<I> MailboxFullError<I> {
+    pub fn into_inner(self) -> I {
+        self.0
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Stash is full ({capacity} messages)")]
+pub struct StashFullError<I> {
+    pub value: I,
+    pub capacity: usize,
+}
+
+impl<I> StashFullError<I> {
+    pub fn into_inner(self) -> I {
+        self.value
+    }
+}
+
+#[derive(Error, Debug, From)]
+pub enum StashError<I> {
+    #[error("{0}")]
+    Full(StashFullError<I>),
+    // a handler stashes at most its own message
+    #[from(skip)]
+    #[error("Handler already stashed its message")]
+    AlreadyStashed(I),
+}
+
+impl<I> StashError<I> {
+    pub fn into_inner(self) -> I {
+        match self {
+            StashError::Full(error) => error.into_inner(),
+            StashError::AlreadyStashed(value) => value,
+        }
+    }
+}
+
+// returned by a handler that stashed its message, the actor drops it instead of reporting an error
+#[derive(Error, Debug)]
+#[error("Message stashed")]
+pub struct Stashed;
+
+#[derive(Error, Debug, From)]
+pub enum TryTellError<I> {
+    #[error("{0}")]
+    MailboxFull(MailboxFullError<I>),
+    #[error("{0}")]
+    ReceiverClosed(ReceiverClosedError),
+}
+
+#[derive(Error, Debug, From)]
+pub enum TryAskError<I, E = OpaqueHandlerError> {
+    #[error("{0}")]
+    MailboxFull(MailboxFullError<I>),
+    #[error("{0}")]
+    Ask(AskError<E>),
+}
+
+#[derive(Error, Debug, From)]
+pub enum TellError {
+    #[error("{0}")]
+    ReceiverClosed(ReceiverClosedError),
+    #[from(skip)]
+    #[error("Mailbox is full")]
+    MailboxFull,
+    #[from(skip)]
+    #[error("Tell timed out waiting for mailbox capacity")]
+    Timeout,
+}
+
+impl<E> From<TellError> for AskError<E> {
+    fn from(value: TellError) -> Self {
+        match value {
+            TellError::ReceiverClosed(error) => AskError::ReceiverClosed(error),
+            TellError::MailboxFull => AskError::MailboxFull,
+            TellError::Timeout => AskError::Timeout,
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -30,8 +158,6 @@ pub enum AskHandlerError<E>
 where
     E: Debug,
 {
-    #[error("Send ok error: {0}")]
-    SendOk(Box<dyn std::error::Error + Send + Sync>),
     #[error("Handle error: {0} and send error: {1}")]
     SendError(E, Box<dyn std::error::Error + Send + Sync>),
     #[error("Handle error: {0}")]
@@ -53,6 +179,8 @@ where
     AskHandlerError(AskHandlerError<E>),
     #[error("Tell handler error: {0}")]
     TellHandlerError(TellHandlerError<E>),
+    #[error("{0} is not a read-only message")]
+    NotReadOnly(&'static str),
 }
 
 impl<E> From<TellHandlerError<E>> for BaseHandlerError<E>
@@ -73,11 +201,12 @@ where
     }
 }
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 #[error("Fatal error {0}")]
-pub struct FatalError(#[source] Box<dyn std::error::Error + Send + Sync>);
+pub struct FatalError(#[source] Arc<dyn std::error::Error + Send + Sync>);
 
-#[derive(Debug)]
+// cloneable so asks can reply with it (`Request -> Response | DefaultHandlerError;`)
+#[derive(Debug, Clone)]
 pub enum DefaultHandlerError {
     Fatal(FatalError),
 }
@@ -95,7 +224,7 @@ where
     E: std::error::Error + Send + Sync + 'static,
 {
     fn from(value: E) -> Self {
-        let error = FatalError(Box::new(value));
+        let error = FatalError(Arc::new(value));
 
         DefaultHandlerError::Fatal(error)
     }
@@ -1,2 +1,5 @@
 pub mod actor;
 pub mod handler;
+pub mod pool;
+pub mod registry;
+pub mod supervisor;
@@ -0,0 +1,5 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("Pool has no workers")]
+pub struct PoolEmptyError;
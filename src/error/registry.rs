@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+use crate::registry::RegistryKey;
+
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    #[error("Actor already registered under {0}")]
+    AlreadyRegistered(RegistryKey),
+    #[error("No actor registered under {0}")]
+    NotFound(RegistryKey),
+    #[error("Actor registered under {0} has different message type")]
+    TypeMismatch(RegistryKey),
+}
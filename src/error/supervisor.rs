@@ -0,0 +1,12 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SupervisorError {
+    #[error("Restart intensity exceeded: more than {max_restarts} restarts in {window:?}")]
+    IntensityExceeded {
+        max_restarts: usize,
+        window: Duration,
+    },
+}
@@ -0,0 +1,160 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    future::{Future, poll_fn},
+    panic::{self, AssertUnwindSafe},
+    pin::{Pin, pin},
+    task::{Context, Poll},
+    thread,
+};
+
+thread_local! {
+    static DROPPING_PANICKED: Cell<bool> = const { Cell::new(false) };
+    static DROPPING_CANCELLED: Cell<bool> = const { Cell::new(false) };
+}
+
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<T> Either<T, T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            Either::Left(value) | Either::Right(value) => value,
+        }
+    }
+}
+
+pub async fn select<L, R>(left: L, right: R) -> Either<L::Output, R::Output>
+where
+    L: Future,
+    R: Future,
+{
+    let mut left = pin!(left);
+    let mut right = pin!(right);
+
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = left.as_mut().poll(cx) {
+            return Poll::Ready(Either::Left(output));
+        }
+
+        if let Poll::Ready(output) = right.as_mut().poll(cx) {
+            return Poll::Ready(Either::Right(output));
+        }
+
+        Poll::Pending
+    })
+    .await
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// polls every future on each wake, meant for a handful of concurrent handlers
+pub struct Concurrent<'a, T> {
+    futures: Vec<BoxFuture<'a, T>>,
+}
+
+impl<'a, T> Concurrent<'a, T> {
+    pub fn new() -> Self {
+        Concurrent {
+            futures: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+
+    pub fn push(&mut self, future: impl Future<Output = T> + Send + 'a) {
+        self.futures.push(Box::pin(future));
+    }
+
+    // never resolves while empty
+    pub async fn next(&mut self) -> T {
+        poll_fn(|cx| {
+            let ready =
+                self.futures
+                    .iter_mut()
+                    .enumerate()
+                    .find_map(|(index, future)| match future.as_mut().poll(cx) {
+                        Poll::Ready(output) => Some((index, output)),
+                        Poll::Pending => None,
+                    });
+
+            match ready {
+                Some((index, output)) => {
+                    drop(self.futures.swap_remove(index));
+
+                    Poll::Ready(output)
+                }
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+pub struct CatchUnwind<F> {
+    future: Option<F>,
+}
+
+impl<F> CatchUnwind<F>
+where
+    F: Future + Unpin,
+{
+    pub fn new(future: F) -> Self {
+        CatchUnwind {
+            future: Some(future),
+        }
+    }
+}
+
+impl<F> Future for CatchUnwind<F>
+where
+    F: Future + Unpin,
+{
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(future) = self.future.as_mut() else {
+            return Poll::Pending;
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| Pin::new(future).poll(cx))) {
+            Ok(Poll::Ready(output)) => {
+                self.future = None;
+
+                Poll::Ready(Ok(output))
+            }
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => {
+                // values captured by the panicked future are dropped here, not during unwinding
+                DROPPING_PANICKED.set(true);
+                self.future = None;
+                DROPPING_PANICKED.set(false);
+
+                Poll::Ready(Err(payload))
+            }
+        }
+    }
+}
+
+pub fn is_dropping_panicked() -> bool {
+    thread::panicking() || DROPPING_PANICKED.get()
+}
+
+// values captured by a handler cut off by a stopping actor can still tell their callers why
+pub fn drop_cancelled<T>(value: T) {
+    DROPPING_CANCELLED.set(true);
+    drop(value);
+    DROPPING_CANCELLED.set(false);
+}
+
+pub fn is_dropping_cancelled() -> bool {
+    DROPPING_CANCELLED.get()
+}
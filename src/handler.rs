@@ -1,8 +1,13 @@
 use std::fmt::{Debug, Display};
 
 use crate::{
-    error::handler::{AskHandlerError, BaseHandlerError, ReceiverHandlerError, TellHandlerError},
-    messaging::{AskMessage, TellMessage},
+    context,
+    error::handler::{
+        AskError, AskHandlerError, BaseHandlerError, OpaqueHandlerError, ReceiverHandlerError,
+        TellHandlerError,
+    },
+    log,
+    messaging::{AskMessage, MessageEnumTrait, MessageRequest, ReplySender, TellMessage},
 };
 use async_trait::async_trait;
 
@@ -11,48 +16,177 @@ pub struct BaseHandler;
 
 #[doc(hidden)]
 #[async_trait]
-pub trait BaseHandlerTrait<A, M, R> {
-    async fn _handle(actor: &mut A, msg: M) -> R;
+pub trait BaseHandlerTrait<A, M, W, R> {
+    async fn _handle(actor: &mut A, msg: W) -> R;
+}
+
+#[doc(hidden)]
+#[async_trait]
+pub trait BaseReadHandlerTrait<A, M, W, R> {
+    async fn _handle_read(actor: &A, msg: W) -> R;
+}
+
+// maps the actor error into the reply error of `Request -> Response | Error;`, the actor error
+// itself works when it is cloneable, other reply types implement this for the actor error
+pub trait ReplyErrorTrait<E>: Sized {
+    fn reply_error(error: &E) -> AskError<Self>;
+}
+
+impl<E> ReplyErrorTrait<E> for OpaqueHandlerError {
+    fn reply_error(_error: &E) -> AskError<Self> {
+        AskError::ReceiverHandlerError(ReceiverHandlerError)
+    }
+}
+
+impl<E> ReplyErrorTrait<E> for E
+where
+    E: Clone,
+{
+    fn reply_error(error: &E) -> AskError<Self> {
+        AskError::Handler(error.clone())
+    }
 }
 
 #[async_trait]
-impl<A, I, O, E> BaseHandlerTrait<A, AskMessage<I, O>, Result<(), AskHandlerError<E>>>
+impl<A, M, I, O, R, E> BaseHandlerTrait<A, M, AskMessage<I, O, R>, Result<(), AskHandlerError<E>>>
     for BaseHandler
 where
     A: AskHandlerTrait<I, O, E> + Sync + Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
+    AskMessage<I, O, R>: MessageRequest<M>,
     I: Send + 'static,
     O: Send + Sync + 'static,
-    E: Display + Debug,
+    R: ReplyErrorTrait<E> + Send + Sync + 'static,
+    E: Display + Debug + Send,
+{
+    async fn _handle(actor: &mut A, msg: AskMessage<I, O, R>) -> Result<(), AskHandlerError<E>> {
+        let AskMessage { request, tx } = msg;
+        let result = actor.handle(request).await;
+
+        reply::<M, I, O, R, E>(tx, result)
+    }
+}
+
+#[async_trait]
+impl<A, M, I, O, R, E>
+    BaseReadHandlerTrait<A, M, AskMessage<I, O, R>, Result<(), AskHandlerError<E>>> for BaseHandler
+where
+    A: ReadAskHandlerTrait<I, O, E> + Sync + Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
+    AskMessage<I, O, R>: MessageRequest<M>,
+    I: Send + 'static,
+    O: Send + Sync + 'static,
+    R: ReplyErrorTrait<E> + Send + Sync + 'static,
+    E: Display + Debug + Send,
+{
+    async fn _handle_read(actor: &A, msg: AskMessage<I, O, R>) -> Result<(), AskHandlerError<E>> {
+        let AskMessage { request, tx } = msg;
+        let result = actor.handle(request).await;
+
+        reply::<M, I, O, R, E>(tx, result)
+    }
+}
+
+fn reply<M, I, O, R, E>(
+    tx: ReplySender<O, R>,
+    result: Result<O, E>,
+) -> Result<(), AskHandlerError<E>>
+where
+    M: MessageEnumTrait + Send + 'static,
+    AskMessage<I, O, R>: MessageRequest<M>,
+    I: 'static,
+    O: Send + Sync + 'static,
+    R: ReplyErrorTrait<E> + Send + Sync + 'static,
+    E: Debug,
 {
-    async fn _handle(actor: &mut A, msg: AskMessage<I, O>) -> Result<(), AskHandlerError<E>> {
-        let result = actor.handle(msg.request).await;
-
-        match result {
-            Ok(data) => msg
-                .tx
-                .send(Ok(data))
-                .map_err(|send_error| AskHandlerError::SendOk(Box::new(send_error))),
-
-            Err(err) => Err(match msg.tx.send(Err(ReceiverHandlerError)) {
-                Ok(_) => AskHandlerError::Handle(err),
-                Err(send_error) => AskHandlerError::SendError(err, Box::new(send_error)),
-            }),
+    let mut tx = Some(tx);
+    let case = AskMessage::get_case();
+
+    if context::restash(|request| {
+        case(AskMessage {
+            request,
+            tx: tx.take().expect("reply sender is only taken once"),
+        })
+    }) {
+        // a stashing handler returns the Stashed sentinel, a reply would never reach the asker
+        if result.is_ok() {
+            log::error(
+                "Ask handler replied after stashing its message - reply dropped".to_string(),
+            );
+        }
+
+        return Ok(());
+    }
+
+    let Some(tx) = tx else {
+        return Ok(());
+    };
+
+    match result {
+        Ok(data) => {
+            if tx.send(Ok(data)).is_err() {
+                log::info("Ask reply dropped - asker stopped waiting".to_string());
+            }
+
+            Ok(())
         }
+
+        Err(err) => Err(match tx.send(Err(R::reply_error(&err))) {
+            Ok(_) => AskHandlerError::Handle(err),
+            Err(send_error) => AskHandlerError::SendError(err, Box::new(send_error)),
+        }),
     }
 }
 
 #[async_trait]
-impl<A, I, E> BaseHandlerTrait<A, TellMessage<I>, Result<(), TellHandlerError<E>>> for BaseHandler
+impl<A, M, I, E> BaseHandlerTrait<A, M, TellMessage<I>, Result<(), TellHandlerError<E>>>
+    for BaseHandler
 where
     A: TellHandlerTrait<I, E> + Sync + Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
+    TellMessage<I>: MessageRequest<M>,
     I: Send + 'static,
     E: Display + Debug,
 {
     async fn _handle(actor: &mut A, msg: TellMessage<I>) -> Result<(), TellHandlerError<E>> {
-        actor.handle(msg.0).await.map_err(TellHandlerError)
+        let result = actor.handle(msg.0).await;
+
+        told::<M, I, E>(result)
     }
 }
 
+#[async_trait]
+impl<A, M, I, E> BaseReadHandlerTrait<A, M, TellMessage<I>, Result<(), TellHandlerError<E>>>
+    for BaseHandler
+where
+    A: ReadTellHandlerTrait<I, E> + Sync + Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
+    TellMessage<I>: MessageRequest<M>,
+    I: Send + 'static,
+    E: Display + Debug,
+{
+    async fn _handle_read(actor: &A, msg: TellMessage<I>) -> Result<(), TellHandlerError<E>> {
+        let result = actor.handle(msg.0).await;
+
+        told::<M, I, E>(result)
+    }
+}
+
+fn told<M, I, E>(result: Result<(), E>) -> Result<(), TellHandlerError<E>>
+where
+    M: MessageEnumTrait + Send + 'static,
+    TellMessage<I>: MessageRequest<M>,
+    I: 'static,
+{
+    let case = TellMessage::get_case();
+
+    if context::restash(|value| case(TellMessage(value))) {
+        return Ok(());
+    }
+
+    result.map_err(TellHandlerError)
+}
+
 #[async_trait]
 pub trait AskHandlerTrait<I, O, E>
 where
@@ -71,6 +205,25 @@ where
     async fn handle(&mut self, msg: I) -> Result<(), E>;
 }
 
+// read-only handlers take a shared reference and may run concurrently with each other
+#[async_trait]
+pub trait ReadAskHandlerTrait<I, O, E>
+where
+    I: Send + 'static,
+    O: Send + 'static,
+    E: Display + Debug,
+{
+    async fn handle(&self, msg: I) -> Result<O, E>;
+}
+
+#[async_trait]
+pub trait ReadTellHandlerTrait<I, E>
+where
+    I: Send + 'static,
+{
+    async fn handle(&self, msg: I) -> Result<(), E>;
+}
+
 #[doc(hidden)]
 #[async_trait]
 pub trait ActorMessageHandlerTrait<M, E>
@@ -78,4 +231,112 @@ where
     E: Display + Debug,
 {
     async fn __handle(&mut self, msg: M) -> Result<(), BaseHandlerError<E>>;
+
+    async fn __handle_read(&self, msg: M) -> Result<(), BaseHandlerError<E>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel,
+        error::{
+            actor::ActorHandleErrorFailure,
+            handler::{AskError, BaseHandlerError, DefaultHandlerError},
+        },
+        handler::{AskHandlerTrait, ReplyErrorTrait},
+        runtime::SpawnFuture,
+        spawn,
+        supervision::{ActorTrait, CommandMessage},
+    };
+
+    struct LookupActor;
+
+    pub struct Fetch(bool);
+    pub struct Lookup(bool);
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("key not found")]
+    struct NotFoundError;
+
+    #[derive(Debug, PartialEq)]
+    pub struct LookupError(String);
+
+    impl ReplyErrorTrait<DefaultHandlerError> for LookupError {
+        fn reply_error(error: &DefaultHandlerError) -> AskError<Self> {
+            AskError::Handler(LookupError(error.to_string()))
+        }
+    }
+
+    crate::match_messages! {
+        actor: LookupActor;
+        error: DefaultHandlerError;
+
+        LookupActorMessage {
+            Fetch -> usize | DefaultHandlerError;
+            Lookup -> usize | LookupError;
+        }
+    }
+
+    #[async_trait]
+    impl ActorTrait<DefaultHandlerError> for LookupActor {
+        async fn on_error(
+            &mut self,
+            _error: BaseHandlerError<DefaultHandlerError>,
+        ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure> {
+            Ok(None)
+        }
+    }
+
+    fn find(found: bool) -> Result<usize, DefaultHandlerError> {
+        if found {
+            Ok(1)
+        } else {
+            Err(NotFoundError.into())
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Fetch, usize, DefaultHandlerError> for LookupActor {
+        async fn handle(&mut self, msg: Fetch) -> Result<usize, DefaultHandlerError> {
+            find(msg.0)
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Lookup, usize, DefaultHandlerError> for LookupActor {
+        async fn handle(&mut self, msg: Lookup) -> Result<usize, DefaultHandlerError> {
+            find(msg.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn ask_replies_with_handler_error() {
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        let actor_ref = spawn(&spawner, LookupActor, bounded_channel(10));
+
+        assert_eq!(actor_ref.ask(Fetch(true)).await.unwrap(), 1);
+
+        match actor_ref.ask(Fetch(false)).await {
+            Err(AskError::Handler(error)) => assert!(error.to_string().contains("key not found")),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn ask_replies_with_mapped_error() {
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        let actor_ref = spawn(&spawner, LookupActor, bounded_channel(10));
+
+        assert_eq!(actor_ref.ask(Lookup(true)).await.unwrap(), 1);
+        assert!(matches!(
+            actor_ref.ask(Lookup(false)).await,
+            Err(AskError::Handler(LookupError(message))) if message.contains("key not found")
+        ));
+    }
 }
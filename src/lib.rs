@@ -1,17 +1,61 @@
+pub mod behavior;
+pub mod context;
+pub mod dead_letter;
 pub mod error;
+mod future;
 pub mod handler;
 mod log;
 pub mod macros;
+pub mod mailbox;
 pub mod messaging;
+pub mod pool;
+mod random;
+pub mod registry;
+pub mod restart;
+pub mod runtime;
+pub mod schedule;
+pub mod spawn;
+mod stash;
+pub mod stats;
 pub mod supervision;
+pub mod supervisor;
+mod timer;
+pub mod watch;
 
+pub use behavior::Admission;
+pub use behavior::Behavior;
+pub use context::ActorContext;
+pub use dead_letter::DeadLetter;
+pub use dead_letter::DeadLetterReason;
 pub use handler::AskHandlerTrait;
 pub use handler::TellHandlerTrait;
+pub use mailbox::OverflowPolicy;
+pub use mailbox::Priority;
 pub use messaging::bounded_channel;
+pub use messaging::bounded_channel_with_overflow;
+pub use messaging::bounded_channel_with_policy;
 pub use messaging::unbounded_channel;
+pub use pool::Pool;
+pub use pool::RoutingStrategy;
+pub use registry::Registry;
+pub use restart::RestartPolicy;
+pub use schedule::TimerHandle;
+pub use spawn::ActorRef;
+pub use spawn::spawn;
+pub use spawn::spawn_with_factory;
+pub use stats::ActorStats;
+pub use supervision::ActorFactoryTrait;
 pub use supervision::ActorTrait;
 pub use supervision::CommandMessage;
+pub use supervision::ExitReason;
+pub use supervision::StopInfo;
+pub use supervision::StopMode;
 pub use supervision::run;
+pub use supervision::run_with_exit;
+pub use supervision::run_with_factory;
+pub use supervisor::RestartStrategy;
+pub use supervisor::Supervisor;
+pub use watch::Terminated;
 
 #[cfg(feature = "macros")]
 pub use ascolt_macros::*;
@@ -1,3 +1,5 @@
+use crate::watch::ActorId;
+
 #[allow(unused_variables)]
 pub fn info(msg: String) {
     #[cfg(feature = "tracing")]
@@ -9,3 +11,73 @@ pub fn error(msg: String) {
     #[cfg(feature = "tracing")]
     tracing::error!(msg)
 }
+
+#[cfg(feature = "tracing")]
+pub type Span = tracing::Span;
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug, Clone)]
+pub struct Span;
+
+pub fn current_span() -> Span {
+    #[cfg(feature = "tracing")]
+    return tracing::Span::current();
+
+    #[cfg(not(feature = "tracing"))]
+    Span
+}
+
+#[allow(unused_variables)]
+pub fn actor_span(id: ActorId, name: Option<String>) -> Span {
+    #[cfg(feature = "tracing")]
+    return tracing::info_span!("actor", actor.id = %id, actor.name = name.as_deref());
+
+    #[cfg(not(feature = "tracing"))]
+    Span
+}
+
+#[allow(unused_variables)]
+pub fn message_span(caller: &Span, id: ActorId, variant: &'static str) -> Span {
+    #[cfg(feature = "tracing")]
+    {
+        // the caller span becomes the parent so traces cross actor boundaries
+        if caller.is_none() {
+            return tracing::info_span!(
+                "message",
+                otel.name = variant,
+                actor.id = %id,
+                message.variant = variant
+            );
+        }
+
+        let span = tracing::info_span!(
+            parent: caller,
+            "message",
+            otel.name = variant,
+            actor.id = %id,
+            message.variant = variant
+        );
+        span.follows_from(tracing::Span::current());
+
+        span
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    Span
+}
+
+#[cfg(feature = "tracing")]
+pub fn instrument<F>(future: F, span: Span) -> tracing::instrument::Instrumented<F>
+where
+    F: Future,
+{
+    tracing::Instrument::instrument(future, span)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub fn instrument<F>(future: F, _span: Span) -> F
+where
+    F: Future,
+{
+    future
+}
@@ -7,7 +7,8 @@ macro_rules! match_messages {
         $(#[$meta:meta])*
         $msg_enum:ident {
             $(
-                $req:ident $(-> $resp:ty)?;
+                $(#[$attr:ident $(($arg:ident))?])*
+                $req:ident $(-> $resp:ty $(| $reply_error:ty)?)?;
             )*
         }
     ) => {
@@ -15,7 +16,7 @@ macro_rules! match_messages {
         pub enum $msg_enum {
             $(
                 $req(
-                    match_messages!(@wrap $req $(, $resp)?)
+                    $crate::match_messages!(@wrap $req $(, $resp $(, $reply_error)?)?)
                 )
             ),*
         }
@@ -23,27 +24,120 @@ macro_rules! match_messages {
         #[async_trait::async_trait]
         impl $crate::handler::ActorMessageHandlerTrait<$msg_enum, $error> for $actor {
             async fn __handle(&mut self, msg: $msg_enum) -> Result<(), $crate::error::handler::BaseHandlerError<$error>> {
-                use $crate::handler::BaseHandlerTrait;
+                match msg {
+                    $(
+                        $msg_enum::$req(inner) => {
+                            $crate::match_messages!(@if_read_only [$($attr $(($arg))?)*] {
+                                <$crate::handler::BaseHandler as $crate::handler::BaseReadHandlerTrait<Self, $msg_enum, _, _>>::_handle_read(&*self, inner).await.map_err(|err| err.into())
+                            } {
+                                <$crate::handler::BaseHandler as $crate::handler::BaseHandlerTrait<Self, $msg_enum, _, _>>::_handle(self, inner).await.map_err(|err| err.into())
+                            })
+                        }
+                    ),*
+                }
+            }
 
+            async fn __handle_read(&self, msg: $msg_enum) -> Result<(), $crate::error::handler::BaseHandlerError<$error>> {
                 match msg {
                     $(
                         $msg_enum::$req(inner) => {
-                            $crate::handler::BaseHandler::_handle(self, inner).await.map_err(|err| err.into())
+                            $crate::match_messages!(@if_read_only [$($attr $(($arg))?)*] {
+                                <$crate::handler::BaseHandler as $crate::handler::BaseReadHandlerTrait<Self, $msg_enum, _, _>>::_handle_read(self, inner).await.map_err(|err| err.into())
+                            } {
+                                // the actor loop only takes this path for variants marked read-only
+                                $crate::messaging::RejectTrait::reject(
+                                    inner,
+                                    $crate::error::handler::AskError::ReceiverHandlerError($crate::error::handler::ReceiverHandlerError),
+                                );
+
+                                Err($crate::error::handler::BaseHandlerError::NotReadOnly(stringify!($req)))
+                            })
                         }
                     ),*
                 }
             }
         }
 
+        impl $crate::messaging::MessageEnumTrait for $msg_enum {
+            fn __reject(self, error: $crate::error::handler::AskError) {
+                use $crate::messaging::RejectTrait;
+
+                match self {
+                    $(
+                        $msg_enum::$req(inner) => inner.reject(error)
+                    ),*
+                }
+            }
+
+            fn __variant(&self) -> &'static str {
+                match self {
+                    $(
+                        $msg_enum::$req(_) => stringify!($req)
+                    ),*
+                }
+            }
+
+            fn __read_only(&self) -> bool {
+                match self {
+                    $(
+                        $msg_enum::$req(_) => $crate::match_messages!(@if_read_only [$($attr $(($arg))?)*] { true } { false })
+                    ),*
+                }
+            }
+        }
+
         $(
-            impl $crate::messaging::MessageRequest<$msg_enum> for match_messages!(@wrap $req $(, $resp)?) {
+            impl $crate::messaging::MessageRequest<$msg_enum> for $crate::match_messages!(@wrap $req $(, $resp $(, $reply_error)?)?) {
                 fn get_case() -> fn(Self) -> $msg_enum {
                     $msg_enum::$req
                 }
+
+                fn variant() -> &'static str {
+                    stringify!($req)
+                }
+
+                fn from_case(msg: $msg_enum) -> Option<Self> {
+                    #[allow(unreachable_patterns)]
+                    match msg {
+                        $msg_enum::$req(inner) => Some(inner),
+                        _ => None,
+                    }
+                }
+
+                $crate::match_messages!(@priority $($attr $(($arg))?)*);
+            }
+
+            impl $crate::messaging::MessageVariantTrait<$msg_enum> for $req {
+                fn variant() -> &'static str {
+                    stringify!($req)
+                }
             }
         )*
     };
 
+    (@if_read_only [read_only $($rest:tt)*] { $($then:tt)* } $else:tt) => {{
+        $($then)*
+    }};
+    (@if_read_only [priority($priority:ident) $($rest:tt)*] $then:tt $else:tt) => {
+        $crate::match_messages!(@if_read_only [$($rest)*] $then $else)
+    };
+    (@if_read_only [] $then:tt { $($else:tt)* }) => {{
+        $($else)*
+    }};
+
+    (@priority priority($priority:ident) $($rest:tt)*) => {
+        fn priority() -> $crate::mailbox::Priority {
+            $crate::mailbox::Priority::$priority
+        }
+    };
+    (@priority read_only $($rest:tt)*) => {
+        $crate::match_messages!(@priority $($rest)*);
+    };
+    (@priority) => {};
+
+    (@wrap $req:ty, $resp:ty, $reply_error:ty) => {
+        $crate::messaging::AskMessage<$req, $resp, $reply_error>
+    };
     (@wrap $req:ty, $resp:ty) => {
         $crate::messaging::AskMessage<$req, $resp>
     };
@@ -0,0 +1,602 @@
+use std::{
+    future::{Future, poll_fn},
+    pin::pin,
+    sync::Arc,
+    task::Poll,
+};
+
+use async_channel::{RecvError, SendError, TryRecvError, TrySendError};
+
+use crate::{
+    schedule::{Scheduled, Scheduler},
+    supervision::ActorMessage,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    #[default]
+    Block,
+    DropNewest,
+    DropOldest,
+    Reject,
+}
+
+struct Overflow<M> {
+    policy: OverflowPolicy,
+    lanes: [async_channel::WeakReceiver<ActorMessage<M>>; 3],
+    slots: async_channel::WeakReceiver<()>,
+    on_drop: Box<dyn Fn(M) + Send + Sync>,
+}
+
+impl<M> Overflow<M> {
+    // evicts from the lowest non-empty lane, None when the mailbox is closed
+    fn drop_oldest(&self) -> Option<()> {
+        let mut closed = true;
+
+        for lane in &self.lanes {
+            match lane.upgrade()?.try_recv() {
+                Ok(oldest) => {
+                    let _ = self.slots.upgrade()?.try_recv();
+                    (self.on_drop)(oldest.msg);
+
+                    return Some(());
+                }
+                Err(TryRecvError::Empty) => closed = false,
+                Err(TryRecvError::Closed) => {}
+            }
+        }
+
+        // slots are taken by senders that did not push their message yet
+        (!closed).then_some(())
+    }
+}
+
+pub(crate) enum MailboxSendError<M> {
+    Closed(SendError<ActorMessage<M>>),
+    Full,
+}
+
+pub(crate) struct MailboxSender<M> {
+    lanes: [async_channel::Sender<ActorMessage<M>>; 3],
+    timers: async_channel::Sender<Scheduled<M>>,
+    slots: Option<async_channel::Sender<()>>,
+    overflow: Option<Arc<Overflow<M>>>,
+}
+
+pub(crate) struct WeakMailboxSender<M> {
+    lanes: [async_channel::WeakSender<ActorMessage<M>>; 3],
+    timers: async_channel::WeakSender<Scheduled<M>>,
+    slots: Option<async_channel::WeakSender<()>>,
+    overflow: Option<Arc<Overflow<M>>>,
+}
+
+pub(crate) struct MailboxReceiver<M> {
+    lanes: [async_channel::Receiver<ActorMessage<M>>; 3],
+    timers: async_channel::Receiver<Scheduled<M>>,
+    slots: Option<async_channel::Receiver<()>>,
+}
+
+pub(crate) fn bounded<M>(mailbox_size: usize) -> (MailboxSender<M>, MailboxReceiver<M>) {
+    // lanes share mailbox_size slots, a sender takes a slot before pushing to its lane
+    let (slots_tx, slots_rx) = async_channel::bounded(mailbox_size);
+    let (mut tx, mut rx) = unbounded();
+
+    tx.slots = Some(slots_tx);
+    rx.slots = Some(slots_rx);
+
+    (tx, rx)
+}
+
+pub(crate) fn bounded_with_overflow<M>(
+    mailbox_size: usize,
+    policy: OverflowPolicy,
+    on_drop: Box<dyn Fn(M) + Send + Sync>,
+) -> (MailboxSender<M>, MailboxReceiver<M>) {
+    let (slots_tx, slots_rx) = async_channel::bounded(mailbox_size);
+    let (mut tx, mut rx) = unbounded();
+
+    tx.overflow = Some(Arc::new(Overflow {
+        policy,
+        lanes: rx.lanes.each_ref().map(|lane| lane.downgrade()),
+        slots: slots_rx.downgrade(),
+        on_drop,
+    }));
+    tx.slots = Some(slots_tx);
+    rx.slots = Some(slots_rx);
+
+    (tx, rx)
+}
+
+pub(crate) fn unbounded<M>() -> (MailboxSender<M>, MailboxReceiver<M>) {
+    let lane = async_channel::unbounded;
+    let [(low_tx, low_rx), (normal_tx, normal_rx), (high_tx, high_rx)] = [lane(), lane(), lane()];
+    let (timers_tx, timers_rx) = async_channel::unbounded();
+
+    (
+        MailboxSender {
+            lanes: [low_tx, normal_tx, high_tx],
+            timers: timers_tx,
+            slots: None,
+            overflow: None,
+        },
+        MailboxReceiver {
+            lanes: [low_rx, normal_rx, high_rx],
+            timers: timers_rx,
+            slots: None,
+        },
+    )
+}
+
+impl<M> MailboxSender<M> {
+    pub async fn send(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+    ) -> Result<(), MailboxSendError<M>> {
+        let Some(overflow) = self.overflow.as_deref() else {
+            return self.send_blocking(msg, priority).await;
+        };
+
+        loop {
+            match self.try_take_slot() {
+                Ok(()) => return self.push(msg, priority).map_err(MailboxSendError::Closed),
+                Err(TrySendError::Closed(())) => {
+                    return Err(MailboxSendError::Closed(SendError(msg)));
+                }
+                Err(TrySendError::Full(())) => {}
+            }
+
+            match overflow.policy {
+                OverflowPolicy::Block => return self.send_blocking(msg, priority).await,
+                OverflowPolicy::DropNewest => {
+                    (overflow.on_drop)(msg.msg);
+
+                    return Ok(());
+                }
+                OverflowPolicy::DropOldest => {
+                    if overflow.drop_oldest().is_none() {
+                        return Err(MailboxSendError::Closed(SendError(msg)));
+                    }
+                }
+                OverflowPolicy::Reject => return Err(MailboxSendError::Full),
+            }
+        }
+    }
+
+    pub fn try_send(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+    ) -> Result<(), TrySendError<ActorMessage<M>>> {
+        match self.try_take_slot() {
+            Ok(()) => self
+                .push(msg, priority)
+                .map_err(|SendError(msg)| TrySendError::Closed(msg)),
+            Err(TrySendError::Full(())) => Err(TrySendError::Full(msg)),
+            Err(TrySendError::Closed(())) => Err(TrySendError::Closed(msg)),
+        }
+    }
+
+    async fn send_blocking(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+    ) -> Result<(), MailboxSendError<M>> {
+        if let Some(slots) = &self.slots {
+            if slots.send(()).await.is_err() {
+                return Err(MailboxSendError::Closed(SendError(msg)));
+            }
+        }
+
+        self.push(msg, priority).map_err(MailboxSendError::Closed)
+    }
+
+    fn try_take_slot(&self) -> Result<(), TrySendError<()>> {
+        match &self.slots {
+            Some(slots) => slots.try_send(()),
+            None => Ok(()),
+        }
+    }
+
+    // lanes are unbounded, the slot taken before bounds the mailbox
+    fn push(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+    ) -> Result<(), SendError<ActorMessage<M>>> {
+        self.lanes[priority as usize]
+            .try_send(msg)
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    pub fn schedule(&self, scheduled: Scheduled<M>) -> Result<(), TrySendError<Scheduled<M>>> {
+        self.timers.try_send(scheduled)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lanes[Priority::Normal as usize].is_closed()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(|lane| lane.len()).sum()
+    }
+
+    pub fn downgrade(&self) -> WeakMailboxSender<M> {
+        WeakMailboxSender {
+            lanes: self.lanes.each_ref().map(|lane| lane.downgrade()),
+            timers: self.timers.downgrade(),
+            slots: self.slots.as_ref().map(|slots| slots.downgrade()),
+            overflow: self.overflow.clone(),
+        }
+    }
+}
+
+impl<M> WeakMailboxSender<M> {
+    pub fn upgrade(&self) -> Option<MailboxSender<M>> {
+        let [low, normal, high] = &self.lanes;
+        let slots = match &self.slots {
+            Some(slots) => Some(slots.upgrade()?),
+            None => None,
+        };
+
+        Some(MailboxSender {
+            lanes: [low.upgrade()?, normal.upgrade()?, high.upgrade()?],
+            timers: self.timers.upgrade()?,
+            slots,
+            overflow: self.overflow.clone(),
+        })
+    }
+}
+
+impl<M> MailboxReceiver<M> {
+    pub async fn recv(&self) -> Result<ActorMessage<M>, RecvError> {
+        let msg = self.recv_lanes().await?;
+        self.free_slot();
+
+        Ok(msg)
+    }
+
+    async fn recv_lanes(&self) -> Result<ActorMessage<M>, RecvError> {
+        let [low, normal, high] = &self.lanes;
+        let mut low = pin!(low.recv());
+        let mut normal = pin!(normal.recv());
+        let mut high = pin!(high.recv());
+
+        poll_fn(|cx| {
+            let mut closed = true;
+
+            for lane in [high.as_mut(), normal.as_mut(), low.as_mut()] {
+                match lane.poll(cx) {
+                    Poll::Ready(Ok(msg)) => return Poll::Ready(Ok(msg)),
+                    Poll::Ready(Err(_)) => {}
+                    Poll::Pending => closed = false,
+                }
+            }
+
+            match closed {
+                true => Poll::Ready(Err(RecvError)),
+                false => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    pub fn try_recv(&self) -> Result<ActorMessage<M>, TryRecvError> {
+        let mut closed = true;
+
+        for lane in self.lanes.iter().rev() {
+            match lane.try_recv() {
+                Ok(msg) => {
+                    self.free_slot();
+
+                    return Ok(msg);
+                }
+                Err(TryRecvError::Empty) => closed = false,
+                Err(TryRecvError::Closed) => {}
+            }
+        }
+
+        match closed {
+            true => Err(TryRecvError::Closed),
+            false => Err(TryRecvError::Empty),
+        }
+    }
+
+    // the sender took its slot before pushing, so a slot is always there to free
+    fn free_slot(&self) {
+        if let Some(slots) = &self.slots {
+            let _ = slots.try_recv();
+        }
+    }
+
+    pub fn scheduler(&self) -> Scheduler<M> {
+        Scheduler::new(self.timers.clone())
+    }
+
+    pub fn close(&self) {
+        for lane in &self.lanes {
+            lane.close();
+        }
+
+        if let Some(slots) = &self.slots {
+            slots.close();
+        }
+
+        self.timers.close();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lanes[Priority::Normal as usize].is_closed()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(|lane| lane.len()).sum()
+    }
+}
+
+impl<M> Clone for MailboxSender<M> {
+    fn clone(&self) -> MailboxSender<M> {
+        MailboxSender {
+            lanes: self.lanes.clone(),
+            timers: self.timers.clone(),
+            slots: self.slots.clone(),
+            overflow: self.overflow.clone(),
+        }
+    }
+}
+
+impl<M> Clone for WeakMailboxSender<M> {
+    fn clone(&self) -> WeakMailboxSender<M> {
+        WeakMailboxSender {
+            lanes: self.lanes.clone(),
+            timers: self.timers.clone(),
+            slots: self.slots.clone(),
+            overflow: self.overflow.clone(),
+        }
+    }
+}
+
+impl<M> Clone for MailboxReceiver<M> {
+    fn clone(&self) -> MailboxReceiver<M> {
+        MailboxReceiver {
+            lanes: self.lanes.clone(),
+            timers: self.timers.clone(),
+            slots: self.slots.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use async_channel::TrySendError;
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel_with_policy,
+        error::handler::{AskError, DefaultHandlerError},
+        handler::{AskHandlerTrait, TellHandlerTrait},
+        log,
+        mailbox::{
+            self, MailboxReceiver, MailboxSendError, MailboxSender, OverflowPolicy, Priority,
+        },
+        runtime::SpawnFuture,
+        spawn,
+        supervision::{ActorMessage, ActorTrait},
+    };
+
+    fn message(value: u32) -> ActorMessage<u32> {
+        ActorMessage {
+            msg: value,
+            sent_at: Instant::now(),
+            ttl: None,
+            span: log::current_span(),
+        }
+    }
+
+    fn received(rx: &MailboxReceiver<u32>) -> Vec<u32> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|msg| msg.msg)
+            .collect()
+    }
+
+    fn try_send(tx: &MailboxSender<u32>, value: u32, priority: Priority) -> bool {
+        tx.try_send(message(value), priority).is_ok()
+    }
+
+    #[tokio::test]
+    async fn higher_lanes_overtake_and_lanes_stay_fifo() {
+        let (tx, rx) = mailbox::unbounded();
+
+        for (value, priority) in [
+            (1, Priority::Normal),
+            (2, Priority::Low),
+            (3, Priority::High),
+            (4, Priority::Normal),
+            (5, Priority::High),
+            (6, Priority::Low),
+        ] {
+            assert!(tx.send(message(value), priority).await.is_ok());
+        }
+
+        assert_eq!(rx.recv().await.unwrap().msg, 3);
+        assert_eq!(received(&rx), [5, 1, 4, 2, 6]);
+    }
+
+    #[test]
+    fn capacity_is_shared_across_lanes() {
+        let (tx, rx) = mailbox::bounded(2);
+
+        assert!(try_send(&tx, 1, Priority::Low));
+        assert!(try_send(&tx, 2, Priority::High));
+        assert!(matches!(
+            tx.try_send(message(3), Priority::Normal),
+            Err(TrySendError::Full(_))
+        ));
+        assert_eq!(tx.len(), 2);
+        assert_eq!(received(&rx), [2, 1]);
+    }
+
+    #[tokio::test]
+    async fn receiving_frees_slots() {
+        let (tx, rx) = mailbox::bounded(1);
+
+        assert!(try_send(&tx, 1, Priority::Normal));
+        assert!(!try_send(&tx, 2, Priority::High));
+
+        assert_eq!(rx.try_recv().unwrap().msg, 1);
+        assert!(try_send(&tx, 2, Priority::High));
+        assert!(!try_send(&tx, 3, Priority::Low));
+
+        assert_eq!(rx.recv().await.unwrap().msg, 2);
+        assert!(try_send(&tx, 3, Priority::Low));
+
+        // a blocked sender gets the slot freed by the receiver
+        let blocked = tx.send(message(4), Priority::Normal);
+        let unblock = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            rx.recv().await.unwrap().msg
+        };
+        let (sent, first) = tokio::join!(blocked, unblock);
+
+        assert!(sent.is_ok());
+        assert_eq!(first, 3);
+        assert_eq!(received(&rx), [4]);
+    }
+
+    fn overflowing(
+        policy: OverflowPolicy,
+    ) -> (
+        MailboxSender<u32>,
+        MailboxReceiver<u32>,
+        Arc<Mutex<Vec<u32>>>,
+    ) {
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let sink = dropped.clone();
+        let (tx, rx) = mailbox::bounded_with_overflow(
+            2,
+            policy,
+            Box::new(move |value| sink.lock().unwrap().push(value)),
+        );
+
+        assert!(try_send(&tx, 1, Priority::High));
+        assert!(try_send(&tx, 2, Priority::Low));
+
+        (tx, rx, dropped)
+    }
+
+    #[tokio::test]
+    async fn block_policy_waits_for_a_slot() {
+        let (tx, rx, dropped) = overflowing(OverflowPolicy::Block);
+
+        let blocked = tx.send(message(3), Priority::Normal);
+        let unblock = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            rx.recv().await.unwrap().msg
+        };
+        let (sent, first) = tokio::join!(blocked, unblock);
+
+        assert!(sent.is_ok());
+        assert_eq!(first, 1);
+        assert_eq!(received(&rx), [3, 2]);
+        assert!(dropped.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn drop_newest_policy_discards_the_sent_message() {
+        let (tx, rx, dropped) = overflowing(OverflowPolicy::DropNewest);
+
+        assert!(tx.send(message(3), Priority::High).await.is_ok());
+        assert_eq!(*dropped.lock().unwrap(), [3]);
+        assert_eq!(received(&rx), [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_policy_evicts_the_lowest_lane_first() {
+        let (tx, rx, dropped) = overflowing(OverflowPolicy::DropOldest);
+
+        assert!(tx.send(message(3), Priority::Normal).await.is_ok());
+        assert!(tx.send(message(4), Priority::Low).await.is_ok());
+        assert_eq!(*dropped.lock().unwrap(), [2, 3]);
+        assert_eq!(received(&rx), [1, 4]);
+    }
+
+    #[tokio::test]
+    async fn reject_policy_refuses_the_sent_message() {
+        let (tx, rx, dropped) = overflowing(OverflowPolicy::Reject);
+
+        assert!(matches!(
+            tx.send(message(3), Priority::High).await,
+            Err(MailboxSendError::Full)
+        ));
+        assert!(dropped.lock().unwrap().is_empty());
+        assert_eq!(received(&rx), [1, 2]);
+    }
+
+    struct IdleActor;
+
+    pub struct Ping;
+    pub struct Touch;
+
+    crate::match_messages! {
+        actor: IdleActor;
+        error: DefaultHandlerError;
+
+        IdleActorMessage {
+            Ping -> ();
+            Touch;
+        }
+    }
+
+    impl ActorTrait<DefaultHandlerError> for IdleActor {}
+
+    #[async_trait]
+    impl AskHandlerTrait<Ping, (), DefaultHandlerError> for IdleActor {
+        async fn handle(&mut self, _msg: Ping) -> Result<(), DefaultHandlerError> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TellHandlerTrait<Touch, DefaultHandlerError> for IdleActor {
+        async fn handle(&mut self, _msg: Touch) -> Result<(), DefaultHandlerError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_ask_resolves_to_mailbox_full() {
+        let (tx, rx) =
+            bounded_channel_with_policy::<IdleActorMessage>(1, OverflowPolicy::DropOldest);
+
+        let evicted = tx.ask(Ping);
+        let overflow = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tx.tell_with_priority(Touch, Priority::High).await
+        };
+        let (evicted, overflow) = tokio::join!(evicted, overflow);
+
+        assert!(overflow.is_ok());
+        assert!(matches!(evicted, Err(AskError::MailboxFull)));
+
+        // the mailbox keeps serving the actor after an eviction
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        spawn(&spawner, IdleActor, (tx.clone(), rx));
+
+        assert!(tx.ask(Ping).await.is_ok());
+    }
+}
@@ -1,51 +1,315 @@
-use std::time::{Duration, Instant};
+use std::{
+    sync::{
+        Arc, Mutex, MutexGuard, Weak,
+        atomic::{AtomicU8, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
+use async_channel::TrySendError;
 use async_trait::async_trait;
 
 use crate::{
-    error::handler::{AskError, ReceiverClosedError, ReceiverHandlerError},
-    supervision::{ActorMessage, CommandMessage},
+    behavior::{Admission, BehaviorRules},
+    dead_letter::{self, DeadLetter, DeadLetterReason, DeadLetterSinkTrait},
+    error::handler::{
+        AskError, MailboxFullError, OpaqueHandlerError, ReceiverClosedError, TellError,
+        TryAskError, TryTellError,
+    },
+    future::{self, Either, select},
+    log,
+    mailbox::{
+        self, MailboxReceiver, MailboxSendError, MailboxSender, OverflowPolicy, Priority,
+        WeakMailboxSender,
+    },
+    schedule::{Scheduled, ScheduledValue, TimerHandle},
+    stats::{self, ActorStats, StatsCounters},
+    supervision::{ActorMessage, ActorStatus, CommandMessage},
+    timer,
+    watch::{self, ActorId, Lifecycle, Terminated, WatchHandle},
 };
 
 #[doc(hidden)]
 #[derive(Debug)]
-pub struct AskMessage<I, O> {
+pub struct AskMessage<I, O, R = OpaqueHandlerError> {
     pub request: I,
-    pub tx: oneshot::Sender<Result<O, ReceiverHandlerError>>,
+    pub tx: ReplySender<O, R>,
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ReplySender<O, R = OpaqueHandlerError> {
+    tx: Option<oneshot::Sender<Result<O, AskError<R>>>>,
+}
+
+impl<O, R> ReplySender<O, R> {
+    pub fn send(
+        mut self,
+        result: Result<O, AskError<R>>,
+    ) -> Result<(), oneshot::SendError<Result<O, AskError<R>>>> {
+        match self.tx.take() {
+            Some(tx) => tx.send(result),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<O, R> Drop for ReplySender<O, R> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            if future::is_dropping_panicked() {
+                let _ = tx.send(Err(AskError::HandlerPanicked));
+            } else if future::is_dropping_cancelled() {
+                let _ = tx.send(Err(AskError::ActorStopping));
+            }
+        }
+    }
 }
 
 #[doc(hidden)]
 #[derive(Debug)]
 pub struct TellMessage<I>(pub I);
 
-pub trait MessageRequest<M> {
+pub trait MessageRequest<M>: Sized {
     fn get_case() -> fn(Self) -> M;
+
+    fn from_case(msg: M) -> Option<Self>;
+
+    fn variant() -> &'static str;
+
+    fn priority() -> Priority {
+        Priority::Normal
+    }
+}
+
+pub trait MessageVariantTrait<M> {
+    fn variant() -> &'static str;
+}
+
+#[doc(hidden)]
+pub trait MessageEnumTrait {
+    fn __reject(self, error: AskError);
+
+    fn __variant(&self) -> &'static str;
+
+    fn __read_only(&self) -> bool;
+}
+
+#[doc(hidden)]
+pub trait RejectTrait {
+    fn reject(self, error: AskError);
+}
+
+impl<I, O, R> RejectTrait for AskMessage<I, O, R> {
+    fn reject(self, error: AskError) {
+        let _ = self.tx.send(Err(error.into_typed()));
+    }
+}
+
+impl<I> RejectTrait for TellMessage<I> {
+    fn reject(self, _error: AskError) {}
 }
 
 pub struct Sender<M> {
-    tx: async_channel::Sender<ActorMessage<M>>,
+    tx: MailboxSender<M>,
+    shared: Arc<ActorShared>,
 }
 pub struct MessageSender<M> {
-    tx: async_channel::Sender<ActorMessage<M>>,
+    tx: MailboxSender<M>,
+    shared: Arc<ActorShared>,
 }
 pub struct CommandSender<M> {
-    tx: async_channel::Sender<ActorMessage<M>>,
+    tx: MailboxSender<M>,
+    shared: Arc<ActorShared>,
+}
+
+pub(crate) struct WeakSender<M> {
+    tx: WeakMailboxSender<M>,
+    shared: Weak<ActorShared>,
+}
+
+pub(crate) struct ActorShared {
+    pub id: ActorId,
+    pub lifecycle: Mutex<Lifecycle>,
+    pub dead_letters: Mutex<Option<Arc<dyn DeadLetterSinkTrait>>>,
+    pub stats: StatsCounters,
+    name: Mutex<Option<String>>,
+    behaviors: Mutex<Vec<BehaviorRules>>,
+    control: async_channel::Sender<CommandMessage>,
+    status: AtomicU8,
+}
+
+impl ActorShared {
+    fn new(control: async_channel::Sender<CommandMessage>) -> Self {
+        ActorShared {
+            id: ActorId::next(),
+            lifecycle: Mutex::new(Lifecycle::default()),
+            dead_letters: Mutex::new(None),
+            stats: StatsCounters::default(),
+            name: Mutex::new(None),
+            behaviors: Mutex::new(Vec::new()),
+            control,
+            status: AtomicU8::new(ActorStatus::Initializing as u8),
+        }
+    }
+
+    pub fn status(&self) -> ActorStatus {
+        ActorStatus::from_u8(self.status.load(Ordering::Acquire))
+    }
+
+    pub fn set_status(&self, status: ActorStatus) {
+        self.status.store(status as u8, Ordering::Release);
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    pub fn set_name(&self, name: String) {
+        *self.name.lock().unwrap_or_else(|err| err.into_inner()) = Some(name);
+    }
+
+    pub fn try_command(&self, command: CommandMessage) -> Result<(), ReceiverClosedError> {
+        self.control
+            .try_send(command)
+            .map_err(|err| ReceiverClosedError::new(Box::new(err)))
+    }
+
+    pub fn behaviors(&self) -> MutexGuard<'_, Vec<BehaviorRules>> {
+        self.behaviors.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // an empty behavior stack accepts every message
+    pub fn admission(&self, variant: &str) -> (Admission, &'static str) {
+        match self.behaviors().last() {
+            Some(behavior) => (behavior.admission(variant), behavior.name()),
+            None => (Admission::Accept, ""),
+        }
+    }
+
+    pub fn init_name(&self, name: impl FnOnce() -> String) {
+        self.name
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get_or_insert_with(name);
+    }
 }
 
 pub fn bounded_channel<M>(mailbox_size: usize) -> (Sender<M>, Receiver<M>) {
-    let (tx, rx) = async_channel::bounded::<ActorMessage<M>>(mailbox_size);
+    channel(|_| mailbox::bounded(mailbox_size))
+}
+
+pub fn bounded_channel_with_policy<M>(
+    mailbox_size: usize,
+    policy: OverflowPolicy,
+) -> (Sender<M>, Receiver<M>)
+where
+    M: MessageEnumTrait + 'static,
+{
+    channel(|shared| {
+        let on_drop = overflow_handler(shared.clone(), None::<fn(&M)>);
 
-    (Sender { tx }, Receiver { rx })
+        mailbox::bounded_with_overflow(mailbox_size, policy, on_drop)
+    })
+}
+
+pub fn bounded_channel_with_overflow<M, F>(
+    mailbox_size: usize,
+    policy: OverflowPolicy,
+    on_overflow: F,
+) -> (Sender<M>, Receiver<M>)
+where
+    M: MessageEnumTrait + 'static,
+    F: Fn(&M) + Send + Sync + 'static,
+{
+    channel(|shared| {
+        let on_drop = overflow_handler(shared.clone(), Some(on_overflow));
+
+        mailbox::bounded_with_overflow(mailbox_size, policy, on_drop)
+    })
+}
+
+fn overflow_handler<M, F>(
+    shared: Arc<ActorShared>,
+    on_overflow: Option<F>,
+) -> Box<dyn Fn(M) + Send + Sync>
+where
+    M: MessageEnumTrait + 'static,
+    F: Fn(&M) + Send + Sync + 'static,
+{
+    Box::new(move |msg| {
+        if let Some(on_overflow) = &on_overflow {
+            on_overflow(&msg);
+        }
+
+        dead_letter::report(&shared, msg.__variant(), DeadLetterReason::Overflow);
+        msg.__reject(AskError::MailboxFull);
+    })
 }
 
 pub fn unbounded_channel<M>() -> (Sender<M>, Receiver<M>) {
-    let (tx, rx) = async_channel::unbounded::<ActorMessage<M>>();
+    channel(|_| mailbox::unbounded())
+}
+
+fn channel<M>(
+    mailbox: impl FnOnce(&Arc<ActorShared>) -> (MailboxSender<M>, MailboxReceiver<M>),
+) -> (Sender<M>, Receiver<M>) {
+    // commands bypass the mailbox so they are never stuck behind queued messages
+    let (control_tx, control_rx) = async_channel::unbounded();
+    let shared = Arc::new(ActorShared::new(control_tx));
+    let (tx, rx) = mailbox(&shared);
+    let myself = tx.downgrade();
 
-    (Sender { tx }, Receiver { rx })
+    (
+        Sender {
+            tx,
+            shared: shared.clone(),
+        },
+        Receiver {
+            rx,
+            control: control_rx,
+            myself,
+            shared,
+        },
+    )
 }
 
 pub struct Receiver<M> {
-    pub rx: async_channel::Receiver<ActorMessage<M>>,
+    pub(crate) rx: MailboxReceiver<M>,
+    pub(crate) control: async_channel::Receiver<CommandMessage>,
+    pub(crate) shared: Arc<ActorShared>,
+    // weak so the actor still stops once every external sender is dropped
+    myself: WeakMailboxSender<M>,
+}
+
+impl<M> Receiver<M> {
+    pub(crate) fn close(&self) {
+        self.rx.close();
+        self.control.close();
+    }
+
+    pub(crate) fn myself(&self) -> WeakSender<M> {
+        WeakSender {
+            tx: self.myself.clone(),
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+}
+
+impl<M> WeakSender<M> {
+    pub fn upgrade(&self) -> Option<Sender<M>> {
+        Some(Sender {
+            tx: self.tx.upgrade()?,
+            shared: self.shared.upgrade()?,
+        })
+    }
+
+    pub fn shared(&self) -> Option<Arc<ActorShared>> {
+        self.shared.upgrade()
+    }
 }
 
 impl<M> Sender<M>
@@ -53,45 +317,227 @@ where
     M: Send + Sync + 'static,
 {
     pub fn into_message_sender(self) -> MessageSender<M> {
-        MessageSender { tx: self.tx }
+        MessageSender {
+            tx: self.tx,
+            shared: self.shared,
+        }
+    }
+
+    pub fn into_command_sender(self) -> CommandSender<M> {
+        CommandSender {
+            tx: self.tx,
+            shared: self.shared,
+        }
+    }
+
+    pub fn id(&self) -> ActorId {
+        self.shared.id
+    }
+
+    pub fn status(&self) -> ActorStatus {
+        self.shared.status()
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.shared.name()
+    }
+
+    pub fn stats(&self) -> ActorStats {
+        stats::snapshot(&self.shared, self.tx.len())
+    }
+
+    pub(crate) fn set_name(&self, name: String) {
+        self.shared.set_name(name);
+    }
+
+    pub fn set_dead_letter_sink<W>(&self, sink: impl Into<MessageSender<W>>)
+    where
+        W: Send + Sync + 'static,
+        TellMessage<DeadLetter>: MessageRequest<W>,
+    {
+        *self
+            .shared
+            .dead_letters
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(sink.into()));
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
     }
 
     pub fn split(self) -> (MessageSender<M>, CommandSender<M>) {
         (
             MessageSender {
                 tx: self.tx.clone(),
+                shared: self.shared.clone(),
+            },
+            CommandSender {
+                tx: self.tx,
+                shared: self.shared,
             },
-            CommandSender { tx: self.tx },
         )
     }
 
+    pub async fn watch<W>(&self, watcher: impl Into<MessageSender<W>>) -> WatchHandle
+    where
+        W: Send + Sync + 'static,
+        TellMessage<Terminated>: MessageRequest<W>,
+    {
+        watch::watch(&self.shared, watcher.into().downgrade()).await
+    }
+
+    pub async fn link<N>(&self, other: &Sender<N>)
+    where
+        N: Send + Sync + 'static,
+    {
+        watch::link(self.downgrade(), other.downgrade()).await
+    }
+
+    pub fn unlink<N>(&self, other: &Sender<N>) {
+        watch::unlink(&self.shared, &other.shared)
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakSender<M> {
+        WeakSender {
+            tx: self.tx.downgrade(),
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    pub(crate) fn queue_len(&self) -> usize {
+        self.tx.len()
+    }
+
+    pub(crate) async fn send_message(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+        variant: &'static str,
+    ) -> Result<(), TellError> {
+        AbstractSenderTrait::send(self, msg, priority, variant).await
+    }
+
     pub async fn command(&self, command: CommandMessage) -> Result<(), ReceiverClosedError> {
-        send_command(self, command).await
+        send_command(&self.shared, command).await
     }
 
-    pub async fn tell<I>(&self, value: I) -> Result<(), ReceiverClosedError>
+    pub async fn tell<I>(&self, value: I) -> Result<(), TellError>
     where
         I: Send,
         TellMessage<I>: MessageRequest<M>,
     {
-        send_tell(self, value, None).await
+        send_tell(self, value, None, None).await
     }
 
-    pub async fn tell_with_ttl<I>(&self, value: I, ttl: Duration) -> Result<(), ReceiverClosedError>
+    pub async fn tell_with_ttl<I>(&self, value: I, ttl: Duration) -> Result<(), TellError>
     where
         I: Send,
         TellMessage<I>: MessageRequest<M>,
     {
-        send_tell(self, value, Some(ttl)).await
+        send_tell(self, value, Some(ttl), None).await
     }
 
-    pub async fn ask<I, O>(&self, value: I) -> Result<O, AskError>
+    pub async fn tell_with_priority<I>(&self, value: I, priority: Priority) -> Result<(), TellError>
     where
         I: Send,
-        AskMessage<I, O>: MessageRequest<M>,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        send_tell(self, value, None, Some(priority)).await
+    }
+
+    pub fn try_tell<I>(&self, value: I) -> Result<(), TryTellError<I>>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        try_send_tell(self, value)
+    }
+
+    pub async fn tell_timeout<I>(&self, value: I, timeout: Duration) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        send_tell_timeout(self, value, timeout).await
+    }
+
+    pub fn send_after<I>(
+        &self,
+        value: I,
+        delay: Duration,
+    ) -> Result<TimerHandle, ReceiverClosedError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        schedule_tell(self, value, delay)
+    }
+
+    pub fn send_interval<I>(
+        &self,
+        value: I,
+        period: Duration,
+    ) -> Result<TimerHandle, ReceiverClosedError>
+    where
+        I: Clone + Send + Sync + 'static,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        schedule_tell_interval(self, value, period)
+    }
+
+    pub async fn ask<I, O, R>(&self, value: I) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
         O: Send,
+        R: Send,
     {
-        send_ask(self, value, None).await
+        send_ask(self, value, None, None).await
+    }
+
+    pub async fn ask_with_ttl<I, O, R>(&self, value: I, ttl: Duration) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask(self, value, Some(ttl), None).await
+    }
+
+    pub async fn ask_with_priority<I, O, R>(
+        &self,
+        value: I,
+        priority: Priority,
+    ) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask(self, value, None, Some(priority)).await
+    }
+
+    pub async fn try_ask<I, O, R>(&self, value: I) -> Result<O, TryAskError<I, R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        try_send_ask(self, value).await
+    }
+
+    pub async fn ask_timeout<I, O, R>(&self, value: I, timeout: Duration) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask_timeout(self, value, timeout).await
     }
 }
 
@@ -99,29 +545,149 @@ impl<M> MessageSender<M>
 where
     M: Send + Sync + 'static,
 {
-    pub async fn tell<I>(&self, value: I) -> Result<(), ReceiverClosedError>
+    pub fn id(&self) -> ActorId {
+        self.shared.id
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    pub fn stats(&self) -> ActorStats {
+        stats::snapshot(&self.shared, self.tx.len())
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakSender<M> {
+        WeakSender {
+            tx: self.tx.downgrade(),
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    pub(crate) fn try_send_message(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+    ) -> Result<(), TrySendError<ActorMessage<M>>> {
+        self.tx.try_send(msg, priority)
+    }
+
+    pub async fn tell<I>(&self, value: I) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        send_tell(self, value, None, None).await
+    }
+
+    pub async fn tell_with_ttl<I>(&self, value: I, ttl: Duration) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        send_tell(self, value, Some(ttl), None).await
+    }
+
+    pub async fn tell_with_priority<I>(&self, value: I, priority: Priority) -> Result<(), TellError>
     where
         I: Send,
         TellMessage<I>: MessageRequest<M>,
     {
-        send_tell(self, value, None).await
+        send_tell(self, value, None, Some(priority)).await
     }
 
-    pub async fn tell_with_ttl<I>(&self, value: I, ttl: Duration) -> Result<(), ReceiverClosedError>
+    pub fn try_tell<I>(&self, value: I) -> Result<(), TryTellError<I>>
     where
         I: Send,
         TellMessage<I>: MessageRequest<M>,
     {
-        send_tell(self, value, Some(ttl)).await
+        try_send_tell(self, value)
+    }
+
+    pub async fn tell_timeout<I>(&self, value: I, timeout: Duration) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        send_tell_timeout(self, value, timeout).await
+    }
+
+    pub fn send_after<I>(
+        &self,
+        value: I,
+        delay: Duration,
+    ) -> Result<TimerHandle, ReceiverClosedError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        schedule_tell(self, value, delay)
+    }
+
+    pub fn send_interval<I>(
+        &self,
+        value: I,
+        period: Duration,
+    ) -> Result<TimerHandle, ReceiverClosedError>
+    where
+        I: Clone + Send + Sync + 'static,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        schedule_tell_interval(self, value, period)
+    }
+
+    pub async fn ask<I, O, R>(&self, value: I) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask(self, value, None, None).await
     }
 
-    pub async fn ask<I, O>(&self, value: I) -> Result<O, AskError>
+    pub async fn ask_with_ttl<I, O, R>(&self, value: I, ttl: Duration) -> Result<O, AskError<R>>
     where
         I: Send,
-        AskMessage<I, O>: MessageRequest<M>,
+        AskMessage<I, O, R>: MessageRequest<M>,
         O: Send,
+        R: Send,
     {
-        send_ask(self, value, None).await
+        send_ask(self, value, Some(ttl), None).await
+    }
+
+    pub async fn ask_with_priority<I, O, R>(
+        &self,
+        value: I,
+        priority: Priority,
+    ) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask(self, value, None, Some(priority)).await
+    }
+
+    pub async fn try_ask<I, O, R>(&self, value: I) -> Result<O, TryAskError<I, R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        try_send_ask(self, value).await
+    }
+
+    pub async fn ask_timeout<I, O, R>(&self, value: I, timeout: Duration) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        send_ask_timeout(self, value, timeout).await
     }
 }
 
@@ -129,97 +695,335 @@ impl<M> CommandSender<M>
 where
     M: Send + Sync + 'static,
 {
+    pub fn id(&self) -> ActorId {
+        self.shared.id
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
     pub async fn command(&self, command: CommandMessage) -> Result<(), ReceiverClosedError> {
-        send_command(self, command).await
+        send_command(&self.shared, command).await
     }
 }
 
-async fn send_command<SE, M>(tx: &SE, command: CommandMessage) -> Result<(), ReceiverClosedError>
+async fn send_command(
+    shared: &ActorShared,
+    command: CommandMessage,
+) -> Result<(), ReceiverClosedError> {
+    shared
+        .control
+        .send(command)
+        .await
+        .map_err(|err| ReceiverClosedError::new(Box::new(err)))
+}
+
+async fn send_tell<SE, M, I>(
+    tx: &SE,
+    value: I,
+    ttl: Option<Duration>,
+    priority: Option<Priority>,
+) -> Result<(), TellError>
 where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Send,
+    TellMessage<I>: MessageRequest<M>,
     M: Send + Sync + 'static,
+{
+    let msg = tell_message(value, ttl);
+
+    tx.send(
+        msg,
+        priority.unwrap_or_else(TellMessage::priority),
+        TellMessage::variant(),
+    )
+    .await
+}
+
+pub(crate) fn tell_message<M, I>(value: I, ttl: Option<Duration>) -> ActorMessage<M>
+where
+    TellMessage<I>: MessageRequest<M>,
+{
+    let case = TellMessage::get_case();
+
+    ActorMessage {
+        msg: case(TellMessage(value)),
+        sent_at: Instant::now(),
+        ttl,
+        span: log::current_span(),
+    }
+}
+
+fn try_send_tell<SE, M, I>(tx: &SE, value: I) -> Result<(), TryTellError<I>>
+where
     SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Send,
+    TellMessage<I>: MessageRequest<M>,
+    M: Send + Sync + 'static,
 {
-    let msg = ActorMessage::CommandMessage(command);
+    let case = TellMessage::get_case();
+    let msg = ActorMessage {
+        msg: case(TellMessage(value)),
+        sent_at: Instant::now(),
+        ttl: None,
+        span: log::current_span(),
+    };
 
-    tx.send(msg).await?;
+    match tx.get_tx().try_send(msg, TellMessage::priority()) {
+        Ok(()) => Ok(()),
+        Err(TrySendError::Full(msg)) => {
+            let TellMessage(value) = take_case(msg);
 
-    Ok(())
+            Err(MailboxFullError(value).into())
+        }
+        Err(err) => {
+            dead_letter::report(
+                tx.get_shared(),
+                TellMessage::variant(),
+                DeadLetterReason::MailboxClosed,
+            );
+
+            Err(ReceiverClosedError::new(Box::new(err)).into())
+        }
+    }
 }
 
-async fn send_tell<SE, M, I>(
+async fn send_tell_timeout<SE, M, I>(tx: &SE, value: I, timeout: Duration) -> Result<(), TellError>
+where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Send,
+    TellMessage<I>: MessageRequest<M>,
+    M: Send + Sync + 'static,
+{
+    match select(send_tell(tx, value, None, None), timer::sleep(timeout)).await {
+        Either::Left(result) => result,
+        Either::Right(_) => Err(TellError::Timeout),
+    }
+}
+
+fn schedule_tell<SE, M, I>(
     tx: &SE,
     value: I,
-    ttl: Option<Duration>,
-) -> Result<(), ReceiverClosedError>
+    delay: Duration,
+) -> Result<TimerHandle, ReceiverClosedError>
 where
     SE: Send + Sync + AbstractSenderTrait<M>,
     I: Send,
     TellMessage<I>: MessageRequest<M>,
     M: Send + Sync + 'static,
 {
-    let tell_message = TellMessage(value);
     let case = TellMessage::get_case();
-    let sent_at = Instant::now();
-    let msg = ActorMessage::RegularMessage {
-        msg: case(tell_message),
-        sent_at,
-        ttl,
-    };
 
-    tx.send(msg).await
+    schedule(tx, ScheduledValue::Once(case(TellMessage(value))), delay)
 }
 
-async fn send_ask<SE, M, I, O>(tx: &SE, value: I, ttl: Option<Duration>) -> Result<O, AskError>
+fn schedule_tell_interval<SE, M, I>(
+    tx: &SE,
+    value: I,
+    period: Duration,
+) -> Result<TimerHandle, ReceiverClosedError>
+where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Clone + Send + Sync + 'static,
+    TellMessage<I>: MessageRequest<M>,
+    M: Send + Sync + 'static,
+{
+    let case = TellMessage::get_case();
+    let value = ScheduledValue::Repeat(Box::new(move || case(TellMessage(value.clone()))), period);
+
+    schedule(tx, value, period)
+}
+
+fn schedule<SE, M>(
+    tx: &SE,
+    value: ScheduledValue<M>,
+    delay: Duration,
+) -> Result<TimerHandle, ReceiverClosedError>
+where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    M: Send + Sync + 'static,
+{
+    let (scheduled, handle) = Scheduled::new(value, Instant::now() + delay);
+
+    tx.get_tx()
+        .schedule(scheduled)
+        .map_err(|err| ReceiverClosedError::new(Box::new(err)))?;
+
+    Ok(handle)
+}
+
+async fn send_ask<SE, M, I, O, R>(
+    tx: &SE,
+    value: I,
+    ttl: Option<Duration>,
+    priority: Option<Priority>,
+) -> Result<O, AskError<R>>
 where
     SE: Send + Sync + AbstractSenderTrait<M>,
     I: Send,
-    AskMessage<I, O>: MessageRequest<M>,
+    AskMessage<I, O, R>: MessageRequest<M>,
     O: Send,
+    R: Send,
     M: Send + Sync + 'static,
+{
+    let (msg, result_rx) = ask_message(value, ttl);
+
+    tx.send(
+        msg,
+        priority.unwrap_or_else(AskMessage::priority),
+        AskMessage::variant(),
+    )
+    .await?;
+
+    receive_reply(result_rx).await
+}
+
+pub(crate) type ReplyReceiver<O, R> = oneshot::Receiver<Result<O, AskError<R>>>;
+
+pub(crate) fn ask_message<M, I, O, R>(
+    value: I,
+    ttl: Option<Duration>,
+) -> (ActorMessage<M>, ReplyReceiver<O, R>)
+where
+    AskMessage<I, O, R>: MessageRequest<M>,
 {
     let (result_tx, result_rx) = oneshot::channel();
     let call_message = AskMessage {
         request: value,
-        tx: result_tx,
+        tx: ReplySender {
+            tx: Some(result_tx),
+        },
     };
     let case = AskMessage::get_case();
-    let sent_at = Instant::now();
-    let msg = ActorMessage::RegularMessage {
+    let msg = ActorMessage {
         msg: case(call_message),
-        sent_at,
+        sent_at: Instant::now(),
         ttl,
+        span: log::current_span(),
     };
 
-    tx.send(msg).await.map_err(AskError::ReceiverClosed)?;
+    (msg, result_rx)
+}
 
+pub(crate) async fn receive_reply<O, R>(result_rx: ReplyReceiver<O, R>) -> Result<O, AskError<R>> {
     result_rx
         .await
         .map_err(|err| AskError::ReceiverClosed(ReceiverClosedError::new(Box::new(err))))?
-        .map_err(AskError::ReceiverHandlerError)
 }
 
-#[async_trait]
-trait AbstractSenderTrait<M>
+async fn try_send_ask<SE, M, I, O, R>(tx: &SE, value: I) -> Result<O, TryAskError<I, R>>
 where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Send,
+    AskMessage<I, O, R>: MessageRequest<M>,
+    O: Send,
+    R: Send,
     M: Send + Sync + 'static,
 {
-    fn get_tx(&self) -> &async_channel::Sender<ActorMessage<M>>;
+    let (result_tx, result_rx) = oneshot::channel();
+    let call_message = AskMessage {
+        request: value,
+        tx: ReplySender {
+            tx: Some(result_tx),
+        },
+    };
+    let case = AskMessage::get_case();
+    let msg = ActorMessage {
+        msg: case(call_message),
+        sent_at: Instant::now(),
+        ttl: None,
+        span: log::current_span(),
+    };
 
-    async fn send(&self, msg: ActorMessage<M>) -> Result<(), ReceiverClosedError> {
-        let tx = self.get_tx();
+    match tx.get_tx().try_send(msg, AskMessage::priority()) {
+        Ok(()) => {}
+        Err(TrySendError::Full(msg)) => {
+            let AskMessage { request, .. } = take_case(msg);
 
-        tx.send(msg)
-            .await
-            .map_err(|err| ReceiverClosedError::new(Box::new(err)))
+            return Err(MailboxFullError(request).into());
+        }
+        Err(err) => {
+            dead_letter::report(
+                tx.get_shared(),
+                AskMessage::variant(),
+                DeadLetterReason::MailboxClosed,
+            );
+
+            let error = AskError::ReceiverClosed(ReceiverClosedError::new(Box::new(err)));
+
+            return Err(error.into());
+        }
     }
+
+    let result = result_rx
+        .await
+        .map_err(|err| AskError::ReceiverClosed(ReceiverClosedError::new(Box::new(err))))?;
+
+    Ok(result?)
 }
 
-impl<M> AbstractSenderTrait<M> for CommandSender<M>
+async fn send_ask_timeout<SE, M, I, O, R>(
+    tx: &SE,
+    value: I,
+    timeout: Duration,
+) -> Result<O, AskError<R>>
 where
+    SE: Send + Sync + AbstractSenderTrait<M>,
+    I: Send,
+    AskMessage<I, O, R>: MessageRequest<M>,
+    O: Send,
+    R: Send,
     M: Send + Sync + 'static,
 {
-    fn get_tx(&self) -> &async_channel::Sender<ActorMessage<M>> {
-        &self.tx
+    match select(
+        send_ask(tx, value, Some(timeout), None),
+        timer::sleep(timeout),
+    )
+    .await
+    {
+        Either::Left(result) => result,
+        Either::Right(_) => Err(AskError::Timeout),
+    }
+}
+
+fn take_case<M, T>(msg: ActorMessage<M>) -> T
+where
+    T: MessageRequest<M>,
+{
+    T::from_case(msg.msg).expect("message case matches its request type")
+}
+
+#[async_trait]
+trait AbstractSenderTrait<M>
+where
+    M: Send + Sync + 'static,
+{
+    fn get_tx(&self) -> &MailboxSender<M>;
+
+    fn get_shared(&self) -> &ActorShared;
+
+    async fn send(
+        &self,
+        msg: ActorMessage<M>,
+        priority: Priority,
+        variant: &'static str,
+    ) -> Result<(), TellError> {
+        let tx = self.get_tx();
+
+        tx.send(msg, priority).await.map_err(|err| {
+            let (reason, error) = match err {
+                MailboxSendError::Closed(err) => (
+                    DeadLetterReason::MailboxClosed,
+                    ReceiverClosedError::new(Box::new(err)).into(),
+                ),
+                MailboxSendError::Full => (DeadLetterReason::Overflow, TellError::MailboxFull),
+            };
+
+            dead_letter::report(self.get_shared(), variant, reason);
+
+            error
+        })
     }
 }
 
@@ -227,24 +1031,33 @@ impl<M> AbstractSenderTrait<M> for MessageSender<M>
 where
     M: Send + Sync + 'static,
 {
-    fn get_tx(&self) -> &async_channel::Sender<ActorMessage<M>> {
+    fn get_tx(&self) -> &MailboxSender<M> {
         &self.tx
     }
+
+    fn get_shared(&self) -> &ActorShared {
+        &self.shared
+    }
 }
 
 impl<M> AbstractSenderTrait<M> for Sender<M>
 where
     M: Send + Sync + 'static,
 {
-    fn get_tx(&self) -> &async_channel::Sender<ActorMessage<M>> {
+    fn get_tx(&self) -> &MailboxSender<M> {
         &self.tx
     }
+
+    fn get_shared(&self) -> &ActorShared {
+        &self.shared
+    }
 }
 
 impl<M> Clone for Sender<M> {
     fn clone(&self) -> Sender<M> {
         Sender {
             tx: self.tx.clone(),
+            shared: self.shared.clone(),
         }
     }
 }
@@ -253,6 +1066,45 @@ impl<M> Clone for MessageSender<M> {
     fn clone(&self) -> MessageSender<M> {
         MessageSender {
             tx: self.tx.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<M> From<Sender<M>> for MessageSender<M>
+where
+    M: Send + Sync + 'static,
+{
+    fn from(value: Sender<M>) -> Self {
+        value.into_message_sender()
+    }
+}
+
+impl<M> From<Sender<M>> for CommandSender<M>
+where
+    M: Send + Sync + 'static,
+{
+    fn from(value: Sender<M>) -> Self {
+        value.into_command_sender()
+    }
+}
+
+impl<M> Clone for WeakSender<M> {
+    fn clone(&self) -> WeakSender<M> {
+        WeakSender {
+            tx: self.tx.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<M> Clone for Receiver<M> {
+    fn clone(&self) -> Receiver<M> {
+        Receiver {
+            rx: self.rx.clone(),
+            control: self.control.clone(),
+            shared: self.shared.clone(),
+            myself: self.myself.clone(),
         }
     }
 }
@@ -261,6 +1113,7 @@ impl<M> Clone for CommandSender<M> {
     fn clone(&self) -> CommandSender<M> {
         CommandSender {
             tx: self.tx.clone(),
+            shared: self.shared.clone(),
         }
     }
 }
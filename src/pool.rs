@@ -0,0 +1,629 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{
+    error::{
+        actor::{ActorJoinError, ActorRuntimeError},
+        handler::{AskError, ReceiverClosedError, TellError},
+        pool::PoolEmptyError,
+    },
+    future::{Either, select},
+    handler::ActorMessageHandlerTrait,
+    mailbox::Priority,
+    messaging::{
+        self, AskMessage, MessageEnumTrait, MessageRequest, Receiver, Sender, TellMessage,
+    },
+    random,
+    runtime::Spawner,
+    spawn,
+    supervision::{ActorTrait, CommandMessage, ExitReason},
+    timer,
+    watch::ActorId,
+};
+
+pub enum RoutingStrategy<M> {
+    RoundRobin,
+    Random,
+    LeastLoaded,
+    ConsistentHash(Arc<dyn Fn(&M) -> u64 + Send + Sync>),
+}
+
+impl<M> RoutingStrategy<M> {
+    pub fn consistent_hash<K>(key: impl Fn(&M) -> K + Send + Sync + 'static) -> Self
+    where
+        K: Hash,
+    {
+        RoutingStrategy::ConsistentHash(Arc::new(move |msg| {
+            // default hasher keys are fixed, so a key keeps its worker across pools and restarts
+            let mut hasher = DefaultHasher::new();
+            key(msg).hash(&mut hasher);
+
+            hasher.finish()
+        }))
+    }
+}
+
+impl<M> Clone for RoutingStrategy<M> {
+    fn clone(&self) -> Self {
+        match self {
+            RoutingStrategy::RoundRobin => RoutingStrategy::RoundRobin,
+            RoutingStrategy::Random => RoutingStrategy::Random,
+            RoutingStrategy::LeastLoaded => RoutingStrategy::LeastLoaded,
+            RoutingStrategy::ConsistentHash(key) => RoutingStrategy::ConsistentHash(key.clone()),
+        }
+    }
+}
+
+pub struct Pool<M> {
+    inner: Arc<PoolInner<M>>,
+}
+
+struct PoolInner<M> {
+    workers: RwLock<Vec<Sender<M>>>,
+    strategy: RoutingStrategy<M>,
+    next: AtomicUsize,
+    spawn_worker: Box<dyn Fn() -> Sender<M> + Send + Sync>,
+}
+
+impl<M> Pool<M>
+where
+    M: MessageEnumTrait + Send + Sync + 'static,
+{
+    pub fn spawn<S, F, C, A, E>(
+        spawner: S,
+        size: usize,
+        strategy: RoutingStrategy<M>,
+        factory: F,
+        channel: C,
+    ) -> Self
+    where
+        S: Spawner + Send + Sync + 'static,
+        F: Fn() -> A + Send + Sync + 'static,
+        C: Fn() -> (Sender<M>, Receiver<M>) + Send + Sync + 'static,
+        A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+        E: Send + Debug + Display + 'static,
+    {
+        let factory = Arc::new(factory);
+
+        let inner = Arc::new_cyclic(|pool: &Weak<PoolInner<M>>| {
+            let pool = pool.clone();
+            let spawn_worker = move || {
+                let factory = factory.clone();
+                let worker = spawn::spawn_with_factory(&spawner, move || factory(), channel());
+                let (sender, join_handle) = worker.into_parts();
+
+                let id = sender.id();
+                let pool = pool.clone();
+
+                // the monitor holds the pool weakly, dropping the pool does not wait for workers
+                spawner.spawn(Box::pin(async move {
+                    let reason = join_handle.await;
+
+                    if let Some(inner) = pool.upgrade() {
+                        Pool { inner }.worker_exited(id, reason);
+                    }
+                }));
+
+                sender
+            };
+
+            PoolInner {
+                workers: RwLock::new(Vec::new()),
+                strategy,
+                next: AtomicUsize::new(0),
+                spawn_worker: Box::new(spawn_worker),
+            }
+        });
+
+        let pool = Pool { inner };
+
+        // spawned under the lock, a worker that exits right away is still found by its monitor
+        pool.workers_mut()
+            .extend((0..size).map(|_| (pool.inner.spawn_worker)()));
+
+        pool
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers().len()
+    }
+
+    pub fn ids(&self) -> Vec<ActorId> {
+        self.workers().iter().map(Sender::id).collect()
+    }
+
+    pub fn senders(&self) -> Vec<Sender<M>> {
+        self.workers().clone()
+    }
+
+    // removed workers drain what they already queued before stopping
+    pub async fn resize(&self, size: usize) {
+        let removed = {
+            let mut workers = self.workers_mut();
+
+            while workers.len() < size {
+                workers.push((self.inner.spawn_worker)());
+            }
+
+            workers.split_off(size)
+        };
+
+        for worker in removed {
+            let _ = worker.command(CommandMessage::DrainAndStopActor).await;
+        }
+    }
+
+    pub async fn command(&self, command: CommandMessage) -> Result<(), ReceiverClosedError> {
+        let mut result = Ok(());
+
+        for worker in self.senders() {
+            if let Err(err) = worker.command(command).await {
+                result = result.and(Err(err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn tell<I>(&self, value: I) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        self.route_tell(value, None, None).await
+    }
+
+    pub async fn tell_with_ttl<I>(&self, value: I, ttl: Duration) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        self.route_tell(value, Some(ttl), None).await
+    }
+
+    pub async fn tell_with_priority<I>(&self, value: I, priority: Priority) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        self.route_tell(value, None, Some(priority)).await
+    }
+
+    pub async fn broadcast<I>(&self, value: I) -> Result<(), TellError>
+    where
+        I: Clone + Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        let workers = self.senders();
+
+        if workers.is_empty() {
+            return Err(pool_empty().into());
+        }
+
+        let mut result = Ok(());
+
+        for worker in workers {
+            if let Err(err) = worker.tell(value.clone()).await {
+                result = result.and(Err(err));
+            }
+        }
+
+        result
+    }
+
+    pub async fn ask<I, O, R>(&self, value: I) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        self.route_ask(value, None, None).await
+    }
+
+    pub async fn ask_with_ttl<I, O, R>(&self, value: I, ttl: Duration) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        self.route_ask(value, Some(ttl), None).await
+    }
+
+    pub async fn ask_with_priority<I, O, R>(
+        &self,
+        value: I,
+        priority: Priority,
+    ) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        self.route_ask(value, None, Some(priority)).await
+    }
+
+    pub async fn ask_timeout<I, O, R>(&self, value: I, timeout: Duration) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        // the ttl lets the worker skip an ask nobody waits for anymore
+        match select(
+            self.route_ask(value, Some(timeout), None),
+            timer::sleep(timeout),
+        )
+        .await
+        {
+            Either::Left(result) => result,
+            Either::Right(_) => Err(AskError::Timeout),
+        }
+    }
+
+    async fn route_tell<I>(
+        &self,
+        value: I,
+        ttl: Option<Duration>,
+        priority: Option<Priority>,
+    ) -> Result<(), TellError>
+    where
+        I: Send,
+        TellMessage<I>: MessageRequest<M>,
+    {
+        let msg = messaging::tell_message(value, ttl);
+        let worker = self.route(&msg.msg).ok_or_else(pool_empty)?;
+
+        worker
+            .send_message(
+                msg,
+                priority.unwrap_or_else(TellMessage::priority),
+                TellMessage::variant(),
+            )
+            .await
+    }
+
+    async fn route_ask<I, O, R>(
+        &self,
+        value: I,
+        ttl: Option<Duration>,
+        priority: Option<Priority>,
+    ) -> Result<O, AskError<R>>
+    where
+        I: Send,
+        AskMessage<I, O, R>: MessageRequest<M>,
+        O: Send,
+        R: Send,
+    {
+        let (msg, result_rx) = messaging::ask_message(value, ttl);
+        let worker = self.route(&msg.msg).ok_or_else(pool_empty)?;
+
+        worker
+            .send_message(
+                msg,
+                priority.unwrap_or_else(AskMessage::priority),
+                AskMessage::variant(),
+            )
+            .await?;
+
+        messaging::receive_reply(result_rx).await
+    }
+
+    fn route(&self, msg: &M) -> Option<Sender<M>> {
+        let workers = self.workers();
+
+        if workers.is_empty() {
+            return None;
+        }
+
+        let index = match &self.inner.strategy {
+            RoutingStrategy::RoundRobin => self.inner.next.fetch_add(1, Ordering::Relaxed),
+            RoutingStrategy::Random => random::random() as usize,
+            RoutingStrategy::LeastLoaded => workers
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, worker)| worker.queue_len())
+                .map_or(0, |(index, _)| index),
+            RoutingStrategy::ConsistentHash(key) => jump_hash(key(msg), workers.len()),
+        };
+
+        Some(workers[index % workers.len()].clone())
+    }
+
+    // failed workers are replaced in place, stopped ones and ones that cannot start leave the pool
+    fn worker_exited(&self, id: ActorId, reason: Result<ExitReason, ActorJoinError>) {
+        let mut workers = self.workers_mut();
+
+        // workers removed by resize are already gone
+        let Some(index) = workers.iter().position(|worker| worker.id() == id) else {
+            return;
+        };
+
+        match reason {
+            Ok(ExitReason::Failed(error))
+                if !matches!(
+                    *error,
+                    ActorRuntimeError::Init(_) | ActorRuntimeError::RestartExhausted { .. }
+                ) =>
+            {
+                workers[index] = (self.inner.spawn_worker)();
+            }
+            _ => {
+                workers.remove(index);
+            }
+        }
+    }
+
+    fn workers(&self) -> RwLockReadGuard<'_, Vec<Sender<M>>> {
+        self.inner
+            .workers
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn workers_mut(&self) -> RwLockWriteGuard<'_, Vec<Sender<M>>> {
+        self.inner
+            .workers
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<M> Clone for Pool<M> {
+    fn clone(&self) -> Pool<M> {
+        Pool {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+fn pool_empty() -> ReceiverClosedError {
+    ReceiverClosedError::new(Box::new(PoolEmptyError))
+}
+
+// jump consistent hash, growing or shrinking the pool only moves keys of the changed workers
+fn jump_hash(mut key: u64, buckets: usize) -> usize {
+    let mut bucket: i64 = -1;
+    let mut next: i64 = 0;
+
+    while next < buckets as i64 {
+        bucket = next;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+
+    bucket as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel,
+        error::{
+            actor::ActorHandleErrorFailure,
+            handler::{BaseHandlerError, DefaultHandlerError},
+        },
+        handler::{AskHandlerTrait, TellHandlerTrait},
+        pool::{Pool, RoutingStrategy, jump_hash},
+        runtime::SpawnFuture,
+        supervision::{ActorTrait, CommandMessage},
+    };
+
+    struct Worker {
+        index: usize,
+        handled: Arc<AtomicUsize>,
+    }
+
+    pub struct Which;
+    pub struct Work;
+    pub struct Crash;
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("worker crashed")]
+    struct WorkerCrashedError;
+
+    crate::match_messages! {
+        actor: Worker;
+        error: DefaultHandlerError;
+
+        WorkerMessage {
+            Which -> usize;
+            Work;
+            Crash;
+        }
+    }
+
+    #[async_trait]
+    impl ActorTrait<DefaultHandlerError> for Worker {
+        // a failing error hook fails the worker instead of stopping it
+        async fn on_error(
+            &mut self,
+            _error: BaseHandlerError<DefaultHandlerError>,
+        ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure> {
+            Err(WorkerCrashedError.into())
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Which, usize, DefaultHandlerError> for Worker {
+        async fn handle(&mut self, _msg: Which) -> Result<usize, DefaultHandlerError> {
+            Ok(self.index)
+        }
+    }
+
+    #[async_trait]
+    impl TellHandlerTrait<Work, DefaultHandlerError> for Worker {
+        async fn handle(&mut self, _msg: Work) -> Result<(), DefaultHandlerError> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.handled.fetch_add(1, Ordering::SeqCst);
+
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TellHandlerTrait<Crash, DefaultHandlerError> for Worker {
+        async fn handle(&mut self, _msg: Crash) -> Result<(), DefaultHandlerError> {
+            Err(WorkerCrashedError.into())
+        }
+    }
+
+    // workers are numbered in start order, replacements get the next number
+    fn worker_pool(
+        size: usize,
+        strategy: RoutingStrategy<WorkerMessage>,
+    ) -> (Pool<WorkerMessage>, Arc<AtomicUsize>) {
+        let started = AtomicUsize::new(0);
+        let handled = Arc::new(AtomicUsize::new(0));
+        let counter = handled.clone();
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+
+        let pool = Pool::spawn(
+            spawner,
+            size,
+            strategy,
+            move || Worker {
+                index: started.fetch_add(1, Ordering::SeqCst),
+                handled: counter.clone(),
+            },
+            || bounded_channel(10),
+        );
+
+        (pool, handled)
+    }
+
+    async fn eventually(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        panic!("condition not reached");
+    }
+
+    async fn route(pool: &Pool<WorkerMessage>, count: usize) -> Vec<usize> {
+        let mut indexes = Vec::new();
+
+        for _ in 0..count {
+            indexes.push(pool.ask(Which).await.unwrap());
+        }
+
+        indexes
+    }
+
+    #[tokio::test]
+    async fn round_robin_spreads_messages() {
+        let (pool, _) = worker_pool(3, RoutingStrategy::RoundRobin);
+
+        assert_eq!(route(&pool, 6).await, [0, 1, 2, 0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn least_loaded_skips_busy_workers() {
+        let (pool, _) = worker_pool(2, RoutingStrategy::LeastLoaded);
+        let busy = &pool.senders()[0];
+
+        for _ in 0..3 {
+            busy.tell(Work).await.unwrap();
+        }
+
+        assert_eq!(route(&pool, 2).await, [1, 1]);
+    }
+
+    #[test]
+    fn jump_hash_moves_only_keys_of_changed_workers() {
+        for key in 0..1000u64 {
+            let key = key.wrapping_mul(0x9e3779b97f4a7c15);
+            let before = jump_hash(key, 4);
+            let grown = jump_hash(key, 5);
+
+            assert!(before < 4);
+            assert!(grown == before || grown == 4);
+            assert_eq!(jump_hash(key, 4), before);
+        }
+    }
+
+    #[tokio::test]
+    async fn consistent_hash_keeps_key_on_worker() {
+        let (pool, _) = worker_pool(4, RoutingStrategy::consistent_hash(|_| "key"));
+        let indexes = route(&pool, 4).await;
+
+        assert!(indexes.iter().all(|index| *index == indexes[0]));
+    }
+
+    #[tokio::test]
+    async fn resize_drains_removed_workers() {
+        let (pool, handled) = worker_pool(2, RoutingStrategy::RoundRobin);
+        let removed = pool.senders()[1].clone();
+
+        for _ in 0..3 {
+            removed.tell(Work).await.unwrap();
+        }
+
+        pool.resize(1).await;
+
+        assert_eq!(pool.size(), 1);
+        assert!(!pool.ids().contains(&removed.id()));
+
+        eventually(|| handled.load(Ordering::SeqCst) == 3).await;
+        assert!(removed.tell(Work).await.is_err());
+
+        pool.resize(3).await;
+
+        assert_eq!(pool.size(), 3);
+        assert_eq!(route(&pool, 3).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_worker_is_replaced() {
+        let (pool, _) = worker_pool(2, RoutingStrategy::RoundRobin);
+        let ids = pool.ids();
+
+        pool.senders()[0].tell(Crash).await.unwrap();
+
+        eventually(|| pool.ids()[0] != ids[0]).await;
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.ids()[1], ids[1]);
+        assert_eq!(route(&pool, 2).await, [2, 1]);
+    }
+
+    #[tokio::test]
+    async fn stopped_worker_is_removed() {
+        let (pool, _) = worker_pool(2, RoutingStrategy::RoundRobin);
+        let ids = pool.ids();
+
+        pool.senders()[0]
+            .command(CommandMessage::StopActor)
+            .await
+            .unwrap();
+
+        eventually(|| pool.size() == 1).await;
+        assert_eq!(pool.ids(), [ids[1]]);
+        assert_eq!(route(&pool, 2).await, [1, 1]);
+    }
+}
@@ -0,0 +1,11 @@
+use std::{collections::hash_map::RandomState, hash::BuildHasher, time::Instant};
+
+// randomly keyed hasher, good enough for jitter and routing without a rand dependency
+pub(crate) fn random() -> u64 {
+    RandomState::new().hash_one(Instant::now())
+}
+
+// uniform in [0, 1)
+pub(crate) fn random_unit() -> f64 {
+    (random() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,179 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+};
+
+use crate::{
+    error::registry::RegistryError,
+    messaging::{CommandSender, MessageSender, Sender},
+};
+
+static GLOBAL_REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RegistryKey {
+    Name(String),
+    Type { id: TypeId, name: &'static str },
+}
+
+impl RegistryKey {
+    pub fn of<K>() -> Self
+    where
+        K: ?Sized + 'static,
+    {
+        RegistryKey::Type {
+            id: TypeId::of::<K>(),
+            name: type_name::<K>(),
+        }
+    }
+}
+
+impl fmt::Display for RegistryKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryKey::Name(name) => write!(f, "name \"{name}\""),
+            RegistryKey::Type { name, .. } => write!(f, "type {name}"),
+        }
+    }
+}
+
+impl From<&str> for RegistryKey {
+    fn from(value: &str) -> Self {
+        RegistryKey::Name(value.to_string())
+    }
+}
+
+impl From<String> for RegistryKey {
+    fn from(value: String) -> Self {
+        RegistryKey::Name(value)
+    }
+}
+
+trait RegisteredSenderTrait: Send + Sync {
+    fn is_closed(&self) -> bool;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<M> RegisteredSenderTrait for Sender<M>
+where
+    M: Send + Sync + 'static,
+{
+    fn is_closed(&self) -> bool {
+        Sender::is_closed(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Registry {
+    entries: Arc<Mutex<HashMap<RegistryKey, Box<dyn RegisteredSenderTrait>>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn global() -> &'static Registry {
+        GLOBAL_REGISTRY.get_or_init(Registry::new)
+    }
+
+    pub fn register<M>(
+        &self,
+        key: impl Into<RegistryKey>,
+        sender: &Sender<M>,
+    ) -> Result<(), RegistryError>
+    where
+        M: Send + Sync + 'static,
+    {
+        let key = key.into();
+        let mut entries = self.lock();
+
+        entries.retain(|_, entry| !entry.is_closed());
+
+        if entries.contains_key(&key) {
+            return Err(RegistryError::AlreadyRegistered(key));
+        }
+
+        if let RegistryKey::Name(name) = &key {
+            sender.set_name(name.clone());
+        }
+
+        entries.insert(key, Box::new(sender.clone()));
+
+        Ok(())
+    }
+
+    pub fn unregister(&self, key: impl Into<RegistryKey>) -> bool {
+        self.lock().remove(&key.into()).is_some()
+    }
+
+    pub fn contains(&self, key: impl Into<RegistryKey>) -> bool {
+        self.lock()
+            .get(&key.into())
+            .is_some_and(|entry| !entry.is_closed())
+    }
+
+    pub fn sender<M>(&self, key: impl Into<RegistryKey>) -> Result<Sender<M>, RegistryError>
+    where
+        M: Send + Sync + 'static,
+    {
+        self.resolve_with(key.into(), Sender::clone)
+    }
+
+    pub fn message_sender<M>(
+        &self,
+        key: impl Into<RegistryKey>,
+    ) -> Result<MessageSender<M>, RegistryError>
+    where
+        M: Send + Sync + 'static,
+    {
+        self.resolve_with(key.into(), |sender| sender.clone().into_message_sender())
+    }
+
+    pub fn command_sender<M>(
+        &self,
+        key: impl Into<RegistryKey>,
+    ) -> Result<CommandSender<M>, RegistryError>
+    where
+        M: Send + Sync + 'static,
+    {
+        self.resolve_with(key.into(), |sender| sender.clone().into_command_sender())
+    }
+
+    fn resolve_with<M, T>(
+        &self,
+        key: RegistryKey,
+        f: impl FnOnce(&Sender<M>) -> T,
+    ) -> Result<T, RegistryError>
+    where
+        M: Send + Sync + 'static,
+    {
+        let mut entries = self.lock();
+
+        let Some(entry) = entries.get(&key) else {
+            return Err(RegistryError::NotFound(key));
+        };
+
+        if entry.is_closed() {
+            entries.remove(&key);
+
+            return Err(RegistryError::NotFound(key));
+        }
+
+        match entry.as_any().downcast_ref::<Sender<M>>() {
+            Some(sender) => Ok(f(sender)),
+            None => Err(RegistryError::TypeMismatch(key)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<RegistryKey, Box<dyn RegisteredSenderTrait>>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use crate::random::random_unit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestartPolicy {
+    pub max_attempts: usize,
+    pub min_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: f64,
+    pub reset_window: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            max_attempts: 3,
+            min_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.2,
+            reset_window: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn new(max_attempts: usize) -> Self {
+        RestartPolicy {
+            max_attempts,
+            ..RestartPolicy::default()
+        }
+    }
+
+    pub fn with_backoff(mut self, min_backoff: Duration, max_backoff: Duration) -> Self {
+        self.min_backoff = min_backoff;
+        self.max_backoff = max_backoff;
+
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = sanitize_jitter(jitter);
+
+        self
+    }
+
+    pub fn with_reset_window(mut self, reset_window: Duration) -> Self {
+        self.reset_window = reset_window;
+
+        self
+    }
+
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let backoff = Duration::from_secs_f64(
+            (self.min_backoff.as_secs_f64() * 2f64.powi(exponent))
+                .min(self.max_backoff.as_secs_f64()),
+        );
+
+        // spread by up to jitter in both directions so restarting actors do not line up
+        let spread = 1.0 + sanitize_jitter(self.jitter) * (2.0 * random_unit() - 1.0);
+
+        backoff.mul_f64(spread).min(self.max_backoff)
+    }
+}
+
+// NaN passes clamp and would panic in mul_f64, a non-finite jitter disables it
+fn sanitize_jitter(jitter: f64) -> f64 {
+    if jitter.is_finite() {
+        jitter.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct RestartTracker {
+    attempts: usize,
+    running_since: Option<Instant>,
+}
+
+impl RestartTracker {
+    pub fn begin(&mut self, policy: &RestartPolicy) {
+        if self
+            .running_since
+            .is_some_and(|running_since| running_since.elapsed() >= policy.reset_window)
+        {
+            self.attempts = 0;
+        }
+    }
+
+    pub fn next_attempt(&mut self) -> usize {
+        self.attempts += 1;
+
+        self.attempts
+    }
+
+    pub fn running(&mut self) {
+        self.running_since = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::restart::RestartPolicy;
+
+    #[test]
+    fn non_finite_jitter_is_disabled() {
+        for jitter in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let policy = RestartPolicy::new(3)
+                .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+                .with_jitter(jitter);
+
+            assert_eq!(policy.jitter, 0.0);
+            assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        }
+
+        // the field is public, so backoff guards against it as well
+        let policy = RestartPolicy {
+            jitter: f64::NAN,
+            ..RestartPolicy::default()
+        };
+
+        assert_eq!(policy.backoff(1), policy.min_backoff);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = RestartPolicy::new(3)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(0.5);
+
+        for attempt in 1..=10 {
+            let backoff = policy.backoff(attempt);
+
+            assert!(backoff >= Duration::from_millis(50));
+            assert!(backoff <= Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::{future::Future, pin::Pin};
+
+pub type SpawnFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+pub trait Spawner {
+    fn spawn(&self, future: SpawnFuture);
+}
+
+impl<F> Spawner for F
+where
+    F: Fn(SpawnFuture),
+{
+    fn spawn(&self, future: SpawnFuture) {
+        self(future)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Default)]
+pub struct TokioSpawner {
+    handle: Option<tokio::runtime::Handle>,
+}
+
+#[cfg(feature = "tokio")]
+impl TokioSpawner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_handle(handle: tokio::runtime::Handle) -> Self {
+        TokioSpawner {
+            handle: Some(handle),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Spawner for TokioSpawner {
+    fn spawn(&self, future: SpawnFuture) {
+        match &self.handle {
+            Some(handle) => drop(handle.spawn(future)),
+            None => drop(tokio::spawn(future)),
+        }
+    }
+}
+
+#[cfg(feature = "smol")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmolSpawner;
+
+#[cfg(feature = "smol")]
+impl Spawner for SmolSpawner {
+    fn spawn(&self, future: SpawnFuture) {
+        smol::spawn(future).detach();
+    }
+}
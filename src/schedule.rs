@@ -0,0 +1,204 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    future::pending,
+    sync::{
+        Arc,
+        atomic::{self, AtomicBool},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    future::{Either, select},
+    log,
+    supervision::ActorMessage,
+    timer::{self, Sleep},
+};
+
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, atomic::Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(atomic::Ordering::Acquire)
+    }
+}
+
+pub(crate) enum ScheduledValue<M> {
+    Once(M),
+    Repeat(Box<dyn Fn() -> M + Send + Sync>, Duration),
+}
+
+pub(crate) struct Scheduled<M> {
+    deadline: Instant,
+    value: ScheduledValue<M>,
+    span: log::Span,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<M> Scheduled<M> {
+    pub fn new(value: ScheduledValue<M>, deadline: Instant) -> (Self, TimerHandle) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let scheduled = Scheduled {
+            deadline,
+            value,
+            span: log::current_span(),
+            cancelled: cancelled.clone(),
+        };
+
+        (scheduled, TimerHandle { cancelled })
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(atomic::Ordering::Acquire)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, atomic::Ordering::Release);
+    }
+}
+
+impl<M> PartialEq for Scheduled<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl<M> Eq for Scheduled<M> {}
+
+impl<M> PartialOrd for Scheduled<M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<M> Ord for Scheduled<M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+// owned by the actor loop, so timers never outlive the actor instance that polls them
+pub(crate) struct Scheduler<M> {
+    queue: BinaryHeap<Scheduled<M>>,
+    registrations: async_channel::Receiver<Scheduled<M>>,
+    // reused across polls so waiting for the next message does not register a new timer each time
+    sleep: Option<Sleep>,
+}
+
+impl<M> Scheduler<M> {
+    pub fn new(registrations: async_channel::Receiver<Scheduled<M>>) -> Self {
+        Scheduler {
+            queue: BinaryHeap::new(),
+            registrations,
+            sleep: None,
+        }
+    }
+
+    pub async fn next(&mut self) -> ActorMessage<M> {
+        loop {
+            while self.queue.peek().is_some_and(Scheduled::is_cancelled) {
+                self.queue.pop();
+            }
+
+            let deadline = self.queue.peek().map(|scheduled| scheduled.deadline);
+
+            if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                if let Some(msg) = self.fire() {
+                    return msg;
+                }
+
+                continue;
+            }
+
+            let sleep = match deadline {
+                Some(deadline) => match &mut self.sleep {
+                    Some(sleep) if sleep.deadline() == deadline => Some(sleep),
+                    sleep => Some(sleep.insert(timer::sleep_until(deadline))),
+                },
+                None => {
+                    self.sleep = None;
+
+                    None
+                }
+            };
+
+            match select(self.registrations.recv(), wait(sleep)).await {
+                Either::Left(Ok(scheduled)) => self.queue.push(scheduled),
+                Either::Left(Err(_)) => wait(self.sleep.as_mut()).await,
+                Either::Right(()) => {}
+            }
+        }
+    }
+
+    fn fire(&mut self) -> Option<ActorMessage<M>> {
+        let Scheduled {
+            deadline: sent_at,
+            value,
+            span,
+            cancelled,
+        } = self.queue.pop()?;
+
+        let msg = match value {
+            ScheduledValue::Once(msg) => msg,
+            ScheduledValue::Repeat(value, period) => {
+                let now = Instant::now();
+                let msg = value();
+
+                // missed ticks are skipped instead of firing in a burst
+                let deadline = match sent_at + period {
+                    next if next > now => next,
+                    _ => now + period,
+                };
+
+                self.queue.push(Scheduled {
+                    deadline,
+                    value: ScheduledValue::Repeat(value, period),
+                    span: span.clone(),
+                    cancelled,
+                });
+
+                msg
+            }
+        };
+
+        Some(ActorMessage {
+            msg,
+            sent_at,
+            ttl: None,
+            span,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.sleep = None;
+
+        for scheduled in self.queue.drain() {
+            scheduled.cancel();
+        }
+
+        while let Ok(scheduled) = self.registrations.try_recv() {
+            scheduled.cancel();
+        }
+    }
+}
+
+impl<M> Drop for Scheduler<M> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+async fn wait(sleep: Option<&mut Sleep>) {
+    match sleep {
+        Some(sleep) => sleep.await,
+        None => pending().await,
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    fmt::{Debug, Display},
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    error::actor::ActorJoinError,
+    handler::ActorMessageHandlerTrait,
+    messaging::{MessageEnumTrait, Receiver, Sender},
+    runtime::Spawner,
+    supervision::{ActorFactoryTrait, ActorStatus, ActorTrait, ExitReason, run_until_exit},
+    watch::{self, ActorId},
+};
+
+pub struct ActorRef<M> {
+    sender: Sender<M>,
+    join_handle: JoinHandle,
+}
+
+pub struct JoinHandle {
+    rx: oneshot::Receiver<ExitReason>,
+}
+
+pub fn spawn<S, A, M, E>(spawner: &S, actor: A, channel: (Sender<M>, Receiver<M>)) -> ActorRef<M>
+where
+    S: Spawner,
+    M: MessageEnumTrait + Send + Sync + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    spawn_actor(spawner, actor, None, channel)
+}
+
+pub fn spawn_with_factory<S, F, A, M, E>(
+    spawner: &S,
+    mut factory: F,
+    channel: (Sender<M>, Receiver<M>),
+) -> ActorRef<M>
+where
+    S: Spawner,
+    F: ActorFactoryTrait<A> + 'static,
+    M: MessageEnumTrait + Send + Sync + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let actor = factory.create();
+
+    spawn_actor(spawner, actor, Some(Box::new(factory)), channel)
+}
+
+fn spawn_actor<S, A, M, E>(
+    spawner: &S,
+    mut actor: A,
+    mut factory: Option<Box<dyn ActorFactoryTrait<A>>>,
+    channel: (Sender<M>, Receiver<M>),
+) -> ActorRef<M>
+where
+    S: Spawner,
+    M: MessageEnumTrait + Send + Sync + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let (sender, rx) = channel;
+    let (exit_tx, exit_rx) = oneshot::channel();
+
+    spawner.spawn(Box::pin(async move {
+        let factory = factory.as_deref_mut().map(|factory| factory as _);
+        let reason = run_until_exit(&mut actor, &rx, factory, None).await;
+
+        watch::terminate(&rx.shared, reason.clone()).await;
+
+        let _ = exit_tx.send(reason);
+    }));
+
+    ActorRef {
+        sender,
+        join_handle: JoinHandle { rx: exit_rx },
+    }
+}
+
+impl<M> ActorRef<M>
+where
+    M: Send + Sync + 'static,
+{
+    pub fn id(&self) -> ActorId {
+        self.sender.id()
+    }
+
+    pub fn status(&self) -> ActorStatus {
+        self.sender.status()
+    }
+
+    pub fn sender(&self) -> &Sender<M> {
+        &self.sender
+    }
+
+    pub fn into_parts(self) -> (Sender<M>, JoinHandle) {
+        (self.sender, self.join_handle)
+    }
+
+    pub async fn join(self) -> Result<ExitReason, ActorJoinError> {
+        self.join_handle.await
+    }
+}
+
+impl<M> Deref for ActorRef<M> {
+    type Target = Sender<M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sender
+    }
+}
+
+impl Future for JoinHandle {
+    type Output = Result<ExitReason, ActorJoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx).map_err(|_| ActorJoinError)
+    }
+}
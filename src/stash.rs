@@ -0,0 +1,101 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    dead_letter::{self, DeadLetterReason},
+    error::handler::AskError,
+    log,
+    messaging::{ActorShared, MessageEnumTrait},
+    supervision::ActorMessage,
+};
+
+pub(crate) struct Stash<M> {
+    capacity: usize,
+    stashed: VecDeque<ActorMessage<M>>,
+    // unstashed messages are handled before anything new from the mailbox
+    unstashed: VecDeque<ActorMessage<M>>,
+}
+
+pub(crate) struct SharedStash<M>(Arc<Mutex<Stash<M>>>);
+
+impl<M> SharedStash<M> {
+    pub fn new(capacity: usize) -> Self {
+        SharedStash(Arc::new(Mutex::new(Stash {
+            capacity,
+            stashed: VecDeque::new(),
+            unstashed: VecDeque::new(),
+        })))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Stash<M>> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<M> Stash<M> {
+    pub fn is_full(&self) -> bool {
+        self.stashed.len() >= self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.stashed.len()
+    }
+
+    // concurrent readers may all pass an earlier is_full check, so the capacity holds here
+    pub fn push(&mut self, msg: ActorMessage<M>) -> Result<(), ActorMessage<M>> {
+        if self.is_full() {
+            return Err(msg);
+        }
+
+        self.stashed.push_back(msg);
+
+        Ok(())
+    }
+
+    pub fn unstash_all(&mut self) -> usize {
+        let count = self.stashed.len();
+
+        while let Some(msg) = self.stashed.pop_back() {
+            self.unstashed.push_front(msg);
+        }
+
+        count
+    }
+
+    pub fn requeue(&mut self, msg: ActorMessage<M>) {
+        self.unstashed.push_front(msg);
+    }
+
+    pub fn pop_unstashed(&mut self) -> Option<ActorMessage<M>> {
+        self.unstashed.pop_front()
+    }
+
+    pub fn take_all(&mut self) -> Vec<ActorMessage<M>> {
+        self.unstashed
+            .drain(..)
+            .chain(self.stashed.drain(..))
+            .collect()
+    }
+}
+
+impl<M> Clone for SharedStash<M> {
+    fn clone(&self) -> SharedStash<M> {
+        SharedStash(self.0.clone())
+    }
+}
+
+// a message that does not fit the stash is dropped like one that does not fit the mailbox
+pub(crate) fn overflow<M>(shared: &ActorShared, msg: M)
+where
+    M: MessageEnumTrait,
+{
+    log::error(format!("Stash is full, dropping {}", msg.__variant()));
+    dead_letter::report(shared, msg.__variant(), DeadLetterReason::Overflow);
+    msg.__reject(AskError::MailboxFull);
+}
@@ -0,0 +1,130 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::messaging::ActorShared;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActorStats {
+    pub queue_depth: usize,
+    pub handled: u64,
+    pub expired: u64,
+    pub errors: u64,
+    pub panics: u64,
+    pub restarts: u64,
+    pub mean_queue_time: Duration,
+    pub max_queue_time: Duration,
+    pub mean_handle_time: Duration,
+    pub max_handle_time: Duration,
+}
+
+#[derive(Default)]
+pub(crate) struct StatsCounters {
+    handled: AtomicU64,
+    expired: AtomicU64,
+    errors: AtomicU64,
+    panics: AtomicU64,
+    restarts: AtomicU64,
+    queue_time_total: AtomicU64,
+    queue_time_max: AtomicU64,
+    handle_time_total: AtomicU64,
+    handle_time_max: AtomicU64,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn mean(total: &AtomicU64, count: u64) -> Duration {
+    match count {
+        0 => Duration::ZERO,
+        count => Duration::from_nanos(total.load(Ordering::Relaxed) / count),
+    }
+}
+
+pub(crate) fn snapshot(shared: &ActorShared, queue_depth: usize) -> ActorStats {
+    let stats = &shared.stats;
+    let handled = stats.handled.load(Ordering::Relaxed);
+
+    ActorStats {
+        queue_depth,
+        handled,
+        expired: stats.expired.load(Ordering::Relaxed),
+        errors: stats.errors.load(Ordering::Relaxed),
+        panics: stats.panics.load(Ordering::Relaxed),
+        restarts: stats.restarts.load(Ordering::Relaxed),
+        mean_queue_time: mean(&stats.queue_time_total, handled),
+        max_queue_time: Duration::from_nanos(stats.queue_time_max.load(Ordering::Relaxed)),
+        mean_handle_time: mean(&stats.handle_time_total, handled),
+        max_handle_time: Duration::from_nanos(stats.handle_time_max.load(Ordering::Relaxed)),
+    }
+}
+
+#[allow(unused_variables)]
+pub(crate) fn record_received(shared: &ActorShared, queue_depth: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("ascolt_mailbox_depth", "actor" => label(shared)).set(queue_depth as f64);
+}
+
+pub(crate) fn record_handled(shared: &ActorShared, queue_time: Duration, handle_time: Duration) {
+    let stats = &shared.stats;
+
+    stats.handled.fetch_add(1, Ordering::Relaxed);
+    stats
+        .queue_time_total
+        .fetch_add(nanos(queue_time), Ordering::Relaxed);
+    stats
+        .queue_time_max
+        .fetch_max(nanos(queue_time), Ordering::Relaxed);
+    stats
+        .handle_time_total
+        .fetch_add(nanos(handle_time), Ordering::Relaxed);
+    stats
+        .handle_time_max
+        .fetch_max(nanos(handle_time), Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    {
+        let actor = label(shared);
+
+        metrics::counter!("ascolt_messages_handled_total", "actor" => actor.clone()).increment(1);
+        metrics::histogram!("ascolt_queue_time_seconds", "actor" => actor.clone())
+            .record(queue_time.as_secs_f64());
+        metrics::histogram!("ascolt_handle_time_seconds", "actor" => actor)
+            .record(handle_time.as_secs_f64());
+    }
+}
+
+pub(crate) fn record_expired(shared: &ActorShared) {
+    shared.stats.expired.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("ascolt_messages_expired_total", "actor" => label(shared)).increment(1);
+}
+
+pub(crate) fn record_error(shared: &ActorShared) {
+    shared.stats.errors.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("ascolt_handler_errors_total", "actor" => label(shared)).increment(1);
+}
+
+pub(crate) fn record_panic(shared: &ActorShared) {
+    shared.stats.panics.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("ascolt_handler_panics_total", "actor" => label(shared)).increment(1);
+}
+
+pub(crate) fn record_restart(shared: &ActorShared) {
+    shared.stats.restarts.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("ascolt_actor_restarts_total", "actor" => label(shared)).increment(1);
+}
+
+#[cfg(feature = "metrics")]
+fn label(shared: &ActorShared) -> String {
+    shared.name().unwrap_or_else(|| shared.id.to_string())
+}
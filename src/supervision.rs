@@ -1,33 +1,128 @@
 use async_trait::async_trait;
 use std::{
-    fmt::{Debug, Display},
+    any::{Any, type_name},
+    fmt::{self, Debug, Display},
+    iter,
+    pin::pin,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
 use crate::{
+    behavior::Admission,
+    context::{self, ActorContext},
+    dead_letter::{self, DeadLetterReason},
     error::{
-        actor::{ActorHandleErrorFailure, ActorInitFailure, ActorRuntimeError, ActorStopFailure},
-        handler::BaseHandlerError,
+        actor::{
+            ActorHandleErrorFailure, ActorInitFailure, ActorPanic, ActorRuntimeError,
+            ActorStopFailure,
+        },
+        handler::{AskError, BaseHandlerError},
     },
+    future::{self, CatchUnwind, Concurrent, Either, select},
     handler::ActorMessageHandlerTrait,
     log,
-    messaging::Receiver,
+    messaging::{ActorShared, MessageEnumTrait, Receiver},
+    restart::{RestartPolicy, RestartTracker},
+    schedule::Scheduler,
+    stash::{self, SharedStash},
+    stats, timer, watch,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommandMessage {
     StopActor,
+    DrainAndStopActor,
+    RejectAndStopActor,
+    DrainAndStopActorWithin(Duration),
     ForceStopActor,
     RestartActor,
+    // stops the actor as failed so its supervisor restarts it, a plain actor just stops
+    EscalateActor,
 }
 
 #[doc(hidden)]
-pub enum ActorMessage<M> {
-    CommandMessage(CommandMessage),
-    RegularMessage {
-        msg: M,
-        sent_at: Instant,
-        ttl: Option<Duration>,
-    },
+pub struct ActorMessage<M> {
+    pub msg: M,
+    pub sent_at: Instant,
+    pub ttl: Option<Duration>,
+    pub span: log::Span,
+}
+
+type HandleResult<E> = Result<Result<(), BaseHandlerError<E>>, Box<dyn Any + Send>>;
+
+enum ActorEvent<M> {
+    Command(CommandMessage),
+    Message(ActorMessage<M>),
+    Shutdown,
+    // every sender is gone, nothing can reach the actor anymore
+    Closed,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExitReason {
+    Stopped,
+    ForceStopped,
+    Failed(Arc<ActorRuntimeError>),
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitReason::Stopped => write!(f, "stopped"),
+            ExitReason::ForceStopped => write!(f, "force stopped"),
+            ExitReason::Failed(error) => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    Immediate,
+    Drain,
+    Reject,
+    DrainWithin(Duration),
+    Restart,
+    Shutdown,
+    Escalate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StopInfo {
+    pub mode: StopMode,
+    pub discarded: usize,
+}
+
+impl StopInfo {
+    fn new(mode: StopMode, discarded: usize) -> Self {
+        StopInfo { mode, discarded }
+    }
+}
+
+#[derive(Debug)]
+pub struct ActorExit<A> {
+    pub actor: A,
+    pub reason: ExitReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ActorStatus {
+    Initializing,
+    Running,
+    Stopping,
+    Stopped,
+}
+
+impl ActorStatus {
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ActorStatus::Initializing,
+            1 => ActorStatus::Running,
+            2 => ActorStatus::Stopping,
+            _ => ActorStatus::Stopped,
+        }
+    }
 }
 
 #[async_trait]
@@ -40,105 +135,1174 @@ where
     }
 
     #[allow(unused_variables, unused_mut)]
-    async fn on_stop(&mut self) -> Result<(), ActorStopFailure> {
+    async fn on_stop(&mut self, info: StopInfo) -> Result<(), ActorStopFailure> {
         Ok(())
     }
 
+    fn restart_policy(&self) -> Option<RestartPolicy> {
+        None
+    }
+
+    fn stash_capacity(&self) -> usize {
+        1024
+    }
+
+    // how many `#[read_only]` handlers may run at once, 1 keeps handling sequential
+    fn max_concurrent_reads(&self) -> usize {
+        1
+    }
+
     #[allow(unused_variables, unused_mut)]
     async fn on_error(
         &mut self,
         error: BaseHandlerError<E>,
     ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure> {
-        Ok(Some(CommandMessage::StopActor))
+        Ok(Some(CommandMessage::EscalateActor))
     }
+
+    #[allow(unused_variables, unused_mut)]
+    async fn on_panic(
+        &mut self,
+        panic: ActorPanic,
+    ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure> {
+        Ok(Some(CommandMessage::EscalateActor))
+    }
+}
+
+pub trait ActorFactoryTrait<A>: Send {
+    fn create(&mut self) -> A;
 }
 
-pub async fn run<A, M, E>(actor: A, rx: Receiver<M>)
+impl<A, F> ActorFactoryTrait<A> for F
 where
-    M: Send + 'static,
+    F: FnMut() -> A + Send,
+{
+    fn create(&mut self) -> A {
+        self()
+    }
+}
+
+pub async fn run<A, M, E>(mut actor: A, rx: Receiver<M>)
+where
+    M: MessageEnumTrait + Send + 'static,
     A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
     E: Send + Debug + Display + 'static,
 {
-    if let Err(error) = run_actor_loop(actor, rx.rx).await {
-        log::error(format!("Actor runtime error: {error}"));
-    }
+    let reason = run_until_exit(&mut actor, &rx, None, None).await;
 
-    log::info("Actor task finished - channel closed".to_string());
+    watch::terminate(&rx.shared, reason).await;
+}
+
+pub async fn run_with_factory<F, A, M, E>(mut factory: F, rx: Receiver<M>)
+where
+    F: ActorFactoryTrait<A>,
+    M: MessageEnumTrait + Send + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let mut actor = factory.create();
+    let reason = run_until_exit(&mut actor, &rx, Some(&mut factory), None).await;
+
+    watch::terminate(&rx.shared, reason).await;
+}
+
+pub async fn run_with_exit<A, M, E>(mut actor: A, rx: Receiver<M>) -> ActorExit<A>
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let reason = run_until_exit(&mut actor, &rx, None, None).await;
+
+    watch::terminate(&rx.shared, reason.clone()).await;
+
+    ActorExit { actor, reason }
+}
+
+pub(crate) async fn run_until_exit<A, M, E>(
+    actor: &mut A,
+    rx: &Receiver<M>,
+    factory: Option<&mut dyn ActorFactoryTrait<A>>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+) -> ExitReason
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    rx.shared.init_name(|| type_name::<A>().to_string());
+
+    let stash = SharedStash::new(actor.stash_capacity());
+    let span = log::actor_span(rx.shared.id, rx.shared.name());
+    let actor_loop = log::instrument(run_actor_loop(actor, rx, &stash, factory, shutdown), span);
+
+    let result = CatchUnwind::new(Box::pin(actor_loop))
+        .await
+        .unwrap_or_else(|payload| Err(ActorPanic::from_payload(payload).into()));
+
+    let reason = match result {
+        Ok(reason) => reason,
+        Err(error) => {
+            log::error(format!("Actor runtime error: {error}"));
+
+            // a failed loop never reaches its stop path, queued asks would wait forever otherwise
+            match shutdown {
+                // a supervised child keeps its mailbox for the restarted actor
+                Some(_) => {
+                    discard_messages(&rx.shared, stash.lock().take_all(), true);
+                }
+                None => {
+                    rx.rx.close();
+                    discard(rx, &stash, true);
+                    rx.close();
+                }
+            }
+
+            ExitReason::Failed(Arc::new(error))
+        }
+    };
+
+    rx.shared.set_status(ActorStatus::Stopped);
+
+    log::info(format!("Actor task finished - {reason}"));
+
+    reason
 }
 
 async fn run_actor_loop<A, M, E>(
-    mut actor: A,
-    rx: async_channel::Receiver<ActorMessage<M>>,
-) -> Result<(), ActorRuntimeError>
+    actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    mut factory: Option<&mut dyn ActorFactoryTrait<A>>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+) -> Result<ExitReason, ActorRuntimeError>
 where
-    M: Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
     A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
     E: Send + Debug + Display + 'static,
 {
-    actor.init().await?;
+    let rx = &receiver.rx;
+    let shared = &receiver.shared;
+    let mut scheduler = rx.scheduler();
+    let mut restarts = RestartTracker::default();
 
-    loop {
-        let msg = rx.recv().await?;
+    // a supervised restart reuses the receiver, the fresh actor must not inherit old behaviors
+    shared.behaviors().clear();
+
+    shared.set_status(ActorStatus::Initializing);
+    init(actor, receiver, stash).await?;
+    shared.set_status(ActorStatus::Running);
+    restarts.running();
+
+    let mut pending = None;
 
-        let command_result = handle_message(&mut actor, msg).await?;
+    let mode = loop {
+        let event = match pending.take() {
+            Some(event) => event,
+            None => next_event(receiver, stash, &mut scheduler, shutdown).await,
+        };
+
+        let command_result = match event {
+            ActorEvent::Command(command) => Some(command),
+            ActorEvent::Message(msg) => {
+                stats::record_received(shared, rx.len());
+
+                let limit = actor.max_concurrent_reads();
+
+                if limit > 1 && msg.msg.__read_only() {
+                    let (command_result, next) = read_concurrently(
+                        actor,
+                        receiver,
+                        stash,
+                        &mut scheduler,
+                        shutdown,
+                        msg,
+                        limit,
+                    )
+                    .await?;
+
+                    pending = next;
+
+                    command_result
+                } else {
+                    handle_message(actor, receiver, stash, msg).await?
+                }
+            }
+            ActorEvent::Shutdown => break StopMode::Shutdown,
+            ActorEvent::Closed => break StopMode::Immediate,
+        };
 
         if let Some(command) = command_result {
             match command {
-                CommandMessage::StopActor => {
-                    rx.close();
-                }
-                CommandMessage::ForceStopActor => {
-                    return Ok(());
+                CommandMessage::StopActor => break StopMode::Immediate,
+                CommandMessage::DrainAndStopActor => break StopMode::Drain,
+                CommandMessage::RejectAndStopActor => break StopMode::Reject,
+                CommandMessage::DrainAndStopActorWithin(timeout) => {
+                    break StopMode::DrainWithin(timeout);
                 }
+                CommandMessage::ForceStopActor => return Ok(force_stop(receiver, stash)),
+                // without a supervisor nothing restarts the actor, so it stops like StopActor
+                CommandMessage::EscalateActor if shutdown.is_none() => break StopMode::Immediate,
+                CommandMessage::EscalateActor => break StopMode::Escalate,
                 CommandMessage::RestartActor => {
-                    actor.on_stop().await?;
+                    stats::record_restart(shared);
+
+                    shared.set_status(ActorStatus::Stopping);
+                    scheduler.clear();
+                    actor.on_stop(StopInfo::new(StopMode::Restart, 0)).await?;
 
-                    actor.init().await?;
+                    stash.lock().unstash_all();
+                    shared.behaviors().clear();
+
+                    let interrupted = restart(
+                        actor,
+                        factory.as_deref_mut(),
+                        receiver,
+                        stash,
+                        shutdown,
+                        &mut restarts,
+                    )
+                    .await?;
+
+                    if let Some(event) = interrupted {
+                        return Ok(abandon_restart(receiver, stash, event));
+                    }
                 }
             };
         }
 
         if rx.is_closed() {
-            break;
+            break StopMode::Immediate;
         }
+    };
+
+    shared.set_status(ActorStatus::Stopping);
+    scheduler.clear();
+
+    // supervisor shutdown and escalation keep the mailbox open for the restarted actor
+    let keep_mailbox = matches!(mode, StopMode::Shutdown | StopMode::Escalate);
+
+    if !keep_mailbox {
+        rx.close();
     }
 
-    actor.on_stop().await?;
+    let discarded = match mode {
+        StopMode::Immediate => discard(receiver, stash, false),
+        StopMode::Reject => discard(receiver, stash, true),
+        StopMode::Drain => match drain(actor, receiver, stash, None).await? {
+            Some(discarded) => discarded,
+            None => return Ok(force_stop(receiver, stash)),
+        },
+        StopMode::DrainWithin(timeout) => {
+            match drain(actor, receiver, stash, Some(Instant::now() + timeout)).await? {
+                Some(discarded) => discarded,
+                None => return Ok(force_stop(receiver, stash)),
+            }
+        }
+        StopMode::Shutdown | StopMode::Escalate => {
+            discard_messages(&receiver.shared, stash.lock().take_all(), true)
+        }
+        StopMode::Restart => 0,
+    };
+
+    if discarded > 0 {
+        log::info(format!(
+            "Actor stopping - discarded {discarded} queued messages"
+        ));
+    }
+
+    if !keep_mailbox {
+        receiver.close();
+    }
+
+    actor.on_stop(StopInfo::new(mode, discarded)).await?;
+
+    match mode {
+        StopMode::Escalate => Ok(ExitReason::Failed(Arc::new(ActorRuntimeError::Escalated))),
+        _ => Ok(ExitReason::Stopped),
+    }
+}
+
+async fn restart<A, M, E>(
+    actor: &mut A,
+    mut factory: Option<&mut (dyn ActorFactoryTrait<A> + '_)>,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+    restarts: &mut RestartTracker,
+) -> Result<Option<ActorEvent<M>>, ActorRuntimeError>
+where
+    M: Send + 'static,
+    A: ActorTrait<E> + Send,
+    E: Send + Debug + Display + 'static,
+{
+    let shared = &receiver.shared;
+    let Some(policy) = actor.restart_policy() else {
+        if let Some(factory) = factory {
+            *actor = factory.create();
+        }
+
+        shared.set_status(ActorStatus::Initializing);
+        init(actor, receiver, stash).await?;
+        shared.set_status(ActorStatus::Running);
+
+        return Ok(None);
+    };
+
+    restarts.begin(&policy);
+
+    let mut init_error = None;
+
+    loop {
+        let attempt = restarts.next_attempt();
+
+        if attempt > policy.max_attempts {
+            return Err(ActorRuntimeError::RestartExhausted {
+                attempts: policy.max_attempts,
+                init: init_error,
+            });
+        }
+
+        let backoff = policy.backoff(attempt);
+        log::info(format!(
+            "Restarting actor in {backoff:?} (attempt {attempt} of {})",
+            policy.max_attempts
+        ));
+
+        shared.set_status(ActorStatus::Initializing);
+
+        if let Some(event) = back_off(receiver, shutdown, backoff).await {
+            return Ok(Some(event));
+        }
+
+        if let Some(factory) = factory.as_deref_mut() {
+            *actor = factory.create();
+        }
+
+        match init(actor, receiver, stash).await {
+            Ok(()) => {
+                shared.set_status(ActorStatus::Running);
+                restarts.running();
+
+                return Ok(None);
+            }
+            Err(error) => {
+                log::error(format!("Restart attempt {attempt} failed: {error}"));
+
+                init_error = Some(error);
+            }
+        }
+    }
+}
+
+// only stop commands cut a backoff short, restart and escalation are already under way
+async fn back_off<M>(
+    receiver: &Receiver<M>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+    backoff: Duration,
+) -> Option<ActorEvent<M>> {
+    let mut sleep = pin!(timer::sleep(backoff));
+
+    loop {
+        let interrupt = async {
+            match shutdown {
+                Some(shutdown) => match select(receiver.control.recv(), shutdown.recv()).await {
+                    Either::Left(command) => command.ok().map(ActorEvent::Command),
+                    Either::Right(_) => Some(ActorEvent::Shutdown),
+                },
+                None => receiver.control.recv().await.ok().map(ActorEvent::Command),
+            }
+        };
+
+        match select(sleep.as_mut(), interrupt).await {
+            Either::Left(()) => return None,
+            Either::Right(Some(ActorEvent::Command(
+                CommandMessage::RestartActor | CommandMessage::EscalateActor,
+            ))) => {}
+            Either::Right(Some(event)) => return Some(event),
+            Either::Right(None) => {
+                sleep.await;
+
+                return None;
+            }
+        }
+    }
+}
+
+// on_stop is skipped, but queued asks must not wait on a mailbox nobody reads anymore
+fn force_stop<M>(receiver: &Receiver<M>, stash: &SharedStash<M>) -> ExitReason
+where
+    M: MessageEnumTrait,
+{
+    receiver.rx.close();
+    let discarded = discard(receiver, stash, true);
+    receiver.close();
+
+    if discarded > 0 {
+        log::info(format!(
+            "Actor force stopped - discarded {discarded} queued messages"
+        ));
+    }
+
+    ExitReason::ForceStopped
+}
+
+// the old actor already ran on_stop and no new one is up, so there is nothing to drain with
+fn abandon_restart<M>(
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    event: ActorEvent<M>,
+) -> ExitReason
+where
+    M: MessageEnumTrait,
+{
+    receiver.shared.set_status(ActorStatus::Stopping);
+
+    let discarded = match event {
+        ActorEvent::Command(CommandMessage::ForceStopActor) => return force_stop(receiver, stash),
+        ActorEvent::Shutdown => discard_messages(&receiver.shared, stash.lock().take_all(), true),
+        event => {
+            let reject = !matches!(event, ActorEvent::Command(CommandMessage::StopActor));
+
+            receiver.rx.close();
+            let discarded = discard(receiver, stash, reject);
+            receiver.close();
+
+            discarded
+        }
+    };
+
+    log::info(format!(
+        "Actor stopped while restarting - discarded {discarded} queued messages"
+    ));
+
+    ExitReason::Stopped
+}
+
+// init runs with a context so it can set up the initial behavior or timers
+async fn init<A, M, E>(
+    actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+) -> Result<(), ActorInitFailure>
+where
+    M: Send + 'static,
+    A: ActorTrait<E> + Send,
+    E: Send + Debug + Display + 'static,
+{
+    let context = ActorContext::new(
+        receiver,
+        stash.clone(),
+        Instant::now(),
+        None,
+        log::current_span(),
+    );
+
+    context::scope(context, actor.init()).await
+}
+
+async fn next_event<M>(
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    scheduler: &mut Scheduler<M>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+) -> ActorEvent<M> {
+    if let Ok(command) = receiver.control.try_recv() {
+        return ActorEvent::Command(command);
+    }
 
-    Ok(())
+    // unstashed messages sit at the head of the mailbox
+    if let Some(msg) = stash.lock().pop_unstashed() {
+        return ActorEvent::Message(msg);
+    }
+
+    let timer = async { ActorEvent::Message(scheduler.next().await) };
+    let message = async {
+        let msg = match shutdown {
+            Some(shutdown) => match select(shutdown.recv(), receiver.rx.recv()).await {
+                Either::Left(_) => return ActorEvent::Shutdown,
+                Either::Right(msg) => msg,
+            },
+            None => receiver.rx.recv().await,
+        };
+
+        msg.map_or(ActorEvent::Closed, ActorEvent::Message)
+    };
+
+    let mut queued = pin!(select(timer, message));
+
+    // control channel is polled first so commands overtake timers and queued messages
+    match select(receiver.control.recv(), queued.as_mut()).await {
+        Either::Left(Ok(command)) => ActorEvent::Command(command),
+        Either::Left(Err(_)) => queued.await.into_inner(),
+        Either::Right(event) => event.into_inner(),
+    }
+}
+
+async fn drain<A, M, E>(
+    actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    deadline: Option<Instant>,
+) -> Result<Option<usize>, ActorHandleErrorFailure>
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    // stashed messages get one more chance before the actor stops
+    stash.lock().unstash_all();
+
+    loop {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok(Some(discard(receiver, stash, true)));
+        }
+
+        if receiver.control.try_recv() == Ok(CommandMessage::ForceStopActor) {
+            return Ok(None);
+        }
+
+        let next = stash.lock().pop_unstashed();
+        let Some(msg) = next.or_else(|| receiver.rx.try_recv().ok()) else {
+            return Ok(Some(discard(receiver, stash, true)));
+        };
+
+        let mut drain_future = Box::pin(handle_message(actor, receiver, stash, msg));
+
+        let command_result = match deadline {
+            Some(deadline) => {
+                match select(drain_future.as_mut(), timer::sleep_until(deadline)).await {
+                    Either::Left(command_result) => command_result?,
+                    Either::Right(_) => {
+                        future::drop_cancelled(drain_future);
+
+                        return Ok(Some(1 + discard(receiver, stash, true)));
+                    }
+                }
+            }
+            None => drain_future.await?,
+        };
+
+        // the actor is already stopping, only a force stop changes the outcome
+        if command_result == Some(CommandMessage::ForceStopActor) {
+            return Ok(None);
+        }
+    }
+}
+
+fn discard<M>(receiver: &Receiver<M>, stash: &SharedStash<M>, reject: bool) -> usize
+where
+    M: MessageEnumTrait,
+{
+    let stashed = stash.lock().take_all();
+    let queued = iter::from_fn(|| receiver.rx.try_recv().ok());
+
+    discard_messages(&receiver.shared, stashed.into_iter().chain(queued), reject)
+}
+
+fn discard_messages<M>(
+    shared: &ActorShared,
+    messages: impl IntoIterator<Item = ActorMessage<M>>,
+    reject: bool,
+) -> usize
+where
+    M: MessageEnumTrait,
+{
+    let mut discarded = 0;
+
+    for msg in messages {
+        dead_letter::report(shared, msg.msg.__variant(), DeadLetterReason::Stopped);
+
+        if reject {
+            msg.msg.__reject(AskError::ActorStopping);
+        }
+
+        discarded += 1;
+    }
+
+    discarded
 }
 
 async fn handle_message<A, M, E>(
     actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
     msg: ActorMessage<M>,
 ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure>
 where
-    M: Send + 'static,
+    M: MessageEnumTrait + Send + 'static,
     A: Send + Sync + ActorMessageHandlerTrait<M, E> + ActorTrait<E> + 'static,
     E: Send + Debug + Display + 'static,
 {
-    let result = match msg {
-        ActorMessage::CommandMessage(command) => Some(command),
+    let span = log::message_span(&msg.span, receiver.shared.id, msg.msg.__variant());
 
-        ActorMessage::RegularMessage { msg, sent_at, ttl } => {
-            if match ttl {
-                Some(ttl) => sent_at.elapsed() > ttl,
-                None => false,
-            } {
-                return Ok(None);
-            }
+    log::instrument(process_message(actor, receiver, stash, msg), span).await
+}
+
+async fn process_message<A, M, E>(
+    actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    msg: ActorMessage<M>,
+) -> Result<Option<CommandMessage>, ActorHandleErrorFailure>
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: Send + Sync + ActorMessageHandlerTrait<M, E> + ActorTrait<E> + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let Some((msg, context)) = admit(receiver, stash, msg) else {
+        return Ok(None);
+    };
+
+    let handle_started_at = Instant::now();
+    let handle_result =
+        CatchUnwind::new(context::scope(context.clone(), actor.__handle(msg))).await;
+
+    record_handled(&receiver.shared, &context, handle_started_at);
+
+    settle(
+        actor,
+        &receiver.shared,
+        handle_result,
+        context.take_requested(),
+    )
+    .await
+}
+
+// read-only messages run side by side until a mutating message or a command needs the actor
+async fn read_concurrently<A, M, E>(
+    actor: &mut A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    scheduler: &mut Scheduler<M>,
+    shutdown: Option<&async_channel::Receiver<()>>,
+    msg: ActorMessage<M>,
+    limit: usize,
+) -> Result<(Option<CommandMessage>, Option<ActorEvent<M>>), ActorRuntimeError>
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: Send + Sync + ActorMessageHandlerTrait<M, E> + ActorTrait<E> + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let mut handled = Vec::new();
+    let mut pending = None;
+
+    {
+        let reader: &A = actor;
+        let mut readers = Concurrent::new();
+        let mut exclusive = false;
+
+        readers.push(read_message(reader, receiver, stash, msg));
 
-            let result = actor
-                .__handle(msg)
+        while !readers.is_empty() {
+            let next = if exclusive || readers.len() >= limit {
+                Either::Left(readers.next().await)
+            } else {
+                select(
+                    readers.next(),
+                    next_event(receiver, stash, scheduler, shutdown),
+                )
                 .await
-                .inspect_err(|err| log::error(format!("{err}")));
+            };
+
+            match next {
+                Either::Left(Some((handle_result, requested))) => {
+                    // failures and requested commands are settled once every reader is done
+                    exclusive |= requested.is_some() || !matches!(handle_result, Ok(Ok(())));
+                    handled.push((handle_result, requested));
+                }
+                Either::Left(None) => {}
+                Either::Right(event) => match event {
+                    ActorEvent::Message(msg) if msg.msg.__read_only() => {
+                        stats::record_received(&receiver.shared, receiver.rx.len());
+                        readers.push(read_message(reader, receiver, stash, msg));
+                    }
+                    ActorEvent::Message(msg) => {
+                        stash.lock().requeue(msg);
+                        exclusive = true;
+                    }
+                    event => {
+                        pending = Some(event);
+                        exclusive = true;
+                    }
+                },
+            }
+        }
+    }
+
+    let mut command_result = None;
+
+    for (handle_result, requested) in handled {
+        let requested = settle(actor, &receiver.shared, handle_result, requested).await?;
+
+        command_result = command_result.or(requested);
+    }
+
+    Ok((command_result, pending))
+}
+
+async fn read_message<A, M, E>(
+    actor: &A,
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    msg: ActorMessage<M>,
+) -> Option<(HandleResult<E>, Option<CommandMessage>)>
+where
+    M: MessageEnumTrait + Send + 'static,
+    A: Send + Sync + ActorMessageHandlerTrait<M, E> + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    let span = log::message_span(&msg.span, receiver.shared.id, msg.msg.__variant());
+
+    log::instrument(
+        async move {
+            let (msg, context) = admit(receiver, stash, msg)?;
+
+            let handle_started_at = Instant::now();
+            let handle_result =
+                CatchUnwind::new(context::scope(context.clone(), actor.__handle_read(msg))).await;
+
+            record_handled(&receiver.shared, &context, handle_started_at);
+
+            Some((handle_result, context.take_requested()))
+        },
+        span,
+    )
+    .await
+}
 
-            match result {
-                Ok(_) => None,
-                Err(err) => actor.on_error(err).await?,
+// expired and behavior filtered messages never reach a handler
+fn admit<M>(
+    receiver: &Receiver<M>,
+    stash: &SharedStash<M>,
+    msg: ActorMessage<M>,
+) -> Option<(M, ActorContext<M>)>
+where
+    M: MessageEnumTrait + Send + 'static,
+{
+    let shared = &receiver.shared;
+    let ActorMessage {
+        msg,
+        sent_at,
+        ttl,
+        span,
+    } = msg;
+
+    if match ttl {
+        Some(ttl) => sent_at.elapsed() > ttl,
+        None => false,
+    } {
+        stats::record_expired(shared);
+        dead_letter::report(shared, msg.__variant(), DeadLetterReason::Expired);
+        msg.__reject(AskError::Expired);
+
+        return None;
+    }
+
+    match shared.admission(msg.__variant()) {
+        (Admission::Accept, _) => {}
+        (Admission::Stash, _) => {
+            let pushed = stash.lock().push(ActorMessage {
+                msg,
+                sent_at,
+                ttl,
+                span,
+            });
+
+            if let Err(ActorMessage { msg, .. }) = pushed {
+                stash::overflow(shared, msg);
             }
+
+            return None;
+        }
+        (Admission::Reject, behavior) => {
+            reject(shared, msg, behavior);
+
+            return None;
+        }
+    }
+
+    let context = ActorContext::new(receiver, stash.clone(), sent_at, ttl, span);
+
+    Some((msg, context))
+}
+
+fn record_handled<M>(shared: &ActorShared, context: &ActorContext<M>, handle_started_at: Instant)
+where
+    M: Send + 'static,
+{
+    stats::record_handled(
+        shared,
+        handle_started_at.duration_since(context.sent_at()),
+        handle_started_at.elapsed(),
+    );
+}
+
+async fn settle<A, E>(
+    actor: &mut A,
+    shared: &ActorShared,
+    handle_result: HandleResult<E>,
+    requested: Option<CommandMessage>,
+) -> Result<Option<CommandMessage>, ActorHandleErrorFailure>
+where
+    A: ActorTrait<E> + Send,
+    E: Send + Debug + Display + 'static,
+{
+    let result = match handle_result {
+        Ok(Ok(_)) => return Ok(requested),
+        Ok(Err(err)) => {
+            stats::record_error(shared);
+            log::error(format!("{err}"));
+
+            actor.on_error(err).await?
         }
+        Err(payload) => {
+            stats::record_panic(shared);
+
+            let panic = ActorPanic::from_payload(payload);
+            log::error(format!("{panic}"));
+
+            actor.on_panic(panic).await?
+        }
+    };
+
+    // a failure hook decision overrides what the handler requested through its context
+    Ok(result.or(requested))
+}
+
+fn reject<M>(shared: &ActorShared, msg: M, behavior: &'static str)
+where
+    M: MessageEnumTrait,
+{
+    dead_letter::report(shared, msg.__variant(), DeadLetterReason::Rejected);
+    msg.__reject(AskError::Rejected(behavior));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
     };
 
-    Ok(result)
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel,
+        error::{
+            actor::{ActorHandleErrorFailure, ActorInitFailure, ActorPanic},
+            handler::{AskError, DefaultHandlerError},
+        },
+        handler::{AskHandlerTrait, ReadAskHandlerTrait, TellHandlerTrait},
+        runtime::SpawnFuture,
+        spawn,
+        spawn::ActorRef,
+        supervision::{ActorTrait, CommandMessage, ExitReason},
+    };
+
+    #[derive(Default)]
+    struct Counters {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+        finished: AtomicUsize,
+    }
+
+    struct ReaderActor {
+        counters: Arc<Counters>,
+    }
+
+    pub struct Read;
+    pub struct Crash;
+    pub struct Write;
+    pub struct Slow;
+
+    crate::match_messages! {
+        actor: ReaderActor;
+        error: DefaultHandlerError;
+
+        ReaderActorMessage {
+            #[read_only] Read -> usize;
+            #[read_only] Crash -> ();
+            Write -> (usize, usize);
+            Slow -> ();
+        }
+    }
+
+    #[async_trait]
+    impl ActorTrait<DefaultHandlerError> for ReaderActor {
+        fn max_concurrent_reads(&self) -> usize {
+            2
+        }
+
+        async fn on_panic(
+            &mut self,
+            _panic: ActorPanic,
+        ) -> Result<Option<CommandMessage>, ActorHandleErrorFailure> {
+            Ok(None)
+        }
+    }
+
+    #[async_trait]
+    impl ReadAskHandlerTrait<Read, usize, DefaultHandlerError> for ReaderActor {
+        async fn handle(&self, _msg: Read) -> Result<usize, DefaultHandlerError> {
+            let active = self.counters.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.counters.peak.fetch_max(active, Ordering::SeqCst);
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            self.counters.active.fetch_sub(1, Ordering::SeqCst);
+            self.counters.finished.fetch_add(1, Ordering::SeqCst);
+
+            Ok(active)
+        }
+    }
+
+    #[async_trait]
+    impl ReadAskHandlerTrait<Crash, (), DefaultHandlerError> for ReaderActor {
+        async fn handle(&self, _msg: Crash) -> Result<(), DefaultHandlerError> {
+            panic!("reader panicked");
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Write, (usize, usize), DefaultHandlerError> for ReaderActor {
+        async fn handle(&mut self, _msg: Write) -> Result<(usize, usize), DefaultHandlerError> {
+            Ok((
+                self.counters.active.load(Ordering::SeqCst),
+                self.counters.finished.load(Ordering::SeqCst),
+            ))
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Slow, (), DefaultHandlerError> for ReaderActor {
+        async fn handle(&mut self, _msg: Slow) -> Result<(), DefaultHandlerError> {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            Ok(())
+        }
+    }
+
+    fn spawn_reader() -> (ActorRef<ReaderActorMessage>, Arc<Counters>) {
+        let counters = Arc::new(Counters::default());
+        let actor = ReaderActor {
+            counters: counters.clone(),
+        };
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+
+        (spawn(&spawner, actor, bounded_channel(100)), counters)
+    }
+
+    fn ask_read(
+        actor_ref: &ActorRef<ReaderActorMessage>,
+    ) -> tokio::task::JoinHandle<Result<usize, AskError>> {
+        let sender = actor_ref.sender().clone();
+
+        tokio::spawn(async move { sender.ask(Read).await })
+    }
+
+    #[tokio::test]
+    async fn concurrent_reads_are_capped() {
+        let (actor_ref, counters) = spawn_reader();
+        let reads = (0..5).map(|_| ask_read(&actor_ref)).collect::<Vec<_>>();
+
+        for read in reads {
+            assert!(read.await.unwrap().is_ok());
+        }
+
+        assert_eq!(counters.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.finished.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn writer_waits_for_readers() {
+        let (actor_ref, _counters) = spawn_reader();
+        let reads = (0..2).map(|_| ask_read(&actor_ref)).collect::<Vec<_>>();
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let (active, finished) = actor_ref.ask(Write).await.unwrap();
+
+        assert_eq!((active, finished), (0, 2));
+
+        for read in reads {
+            assert!(read.await.unwrap().is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn command_waits_for_readers() {
+        let (actor_ref, counters) = spawn_reader();
+        let reads = (0..2).map(|_| ask_read(&actor_ref)).collect::<Vec<_>>();
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        actor_ref.command(CommandMessage::StopActor).await.unwrap();
+
+        for read in reads {
+            assert!(read.await.unwrap().is_ok());
+        }
+
+        assert!(matches!(actor_ref.join().await, Ok(ExitReason::Stopped)));
+        assert_eq!(counters.finished.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn reader_panic_is_isolated() {
+        let (actor_ref, _counters) = spawn_reader();
+        let read = ask_read(&actor_ref);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let panicked = actor_ref.ask(Crash).await;
+
+        assert!(matches!(panicked, Err(AskError::HandlerPanicked)));
+        assert!(read.await.unwrap().is_ok());
+        assert_eq!(actor_ref.ask(Write).await.unwrap(), (0, 1));
+    }
+
+    fn ask_slow(
+        actor_ref: &ActorRef<ReaderActorMessage>,
+    ) -> tokio::task::JoinHandle<Result<(), AskError>> {
+        let sender = actor_ref.sender().clone();
+
+        tokio::spawn(async move { sender.ask(Slow).await })
+    }
+
+    #[tokio::test]
+    async fn force_stop_rejects_queued_asks() {
+        let (actor_ref, _counters) = spawn_reader();
+        let running = ask_slow(&actor_ref);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let queued = ask_slow(&actor_ref);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        actor_ref
+            .command(CommandMessage::ForceStopActor)
+            .await
+            .unwrap();
+
+        assert!(running.await.unwrap().is_ok());
+        assert!(matches!(
+            queued.await.unwrap(),
+            Err(AskError::ActorStopping)
+        ));
+        assert!(matches!(
+            actor_ref.join().await,
+            Ok(ExitReason::ForceStopped)
+        ));
+    }
+
+    #[tokio::test]
+    async fn drain_deadline_rejects_running_ask() {
+        let (actor_ref, _counters) = spawn_reader();
+        let running = ask_slow(&actor_ref);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // drained after the running ask, the deadline cuts it off halfway
+        let drained = ask_slow(&actor_ref);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        actor_ref
+            .command(CommandMessage::DrainAndStopActorWithin(
+                Duration::from_millis(30),
+            ))
+            .await
+            .unwrap();
+
+        assert!(running.await.unwrap().is_ok());
+        assert!(matches!(
+            drained.await.unwrap(),
+            Err(AskError::ActorStopping)
+        ));
+        assert!(matches!(actor_ref.join().await, Ok(ExitReason::Stopped)));
+    }
+
+    struct LifecycleActor {
+        fail_init: bool,
+    }
+
+    pub struct Ping;
+    pub struct Fail;
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("lifecycle failure")]
+    struct LifecycleError;
+
+    crate::match_messages! {
+        actor: LifecycleActor;
+        error: DefaultHandlerError;
+
+        LifecycleActorMessage {
+            Ping -> ();
+            Fail;
+        }
+    }
+
+    #[async_trait]
+    impl ActorTrait<DefaultHandlerError> for LifecycleActor {
+        async fn init(&mut self) -> Result<(), ActorInitFailure> {
+            if self.fail_init {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+
+                return Err(LifecycleError.into());
+            }
+
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Ping, (), DefaultHandlerError> for LifecycleActor {
+        async fn handle(&mut self, _msg: Ping) -> Result<(), DefaultHandlerError> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TellHandlerTrait<Fail, DefaultHandlerError> for LifecycleActor {
+        async fn handle(&mut self, _msg: Fail) -> Result<(), DefaultHandlerError> {
+            Err(LifecycleError.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_actor_rejects_queued_asks() {
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        let actor_ref = spawn(
+            &spawner,
+            LifecycleActor { fail_init: true },
+            bounded_channel(10),
+        );
+
+        let queued = actor_ref.ask(Ping).await;
+
+        assert!(matches!(queued, Err(AskError::ActorStopping)));
+        assert!(matches!(actor_ref.join().await, Ok(ExitReason::Failed(_))));
+    }
+
+    #[tokio::test]
+    async fn unsupervised_handler_error_stops_actor() {
+        let spawner = |future: SpawnFuture| {
+            tokio::spawn(future);
+        };
+        let actor_ref = spawn(
+            &spawner,
+            LifecycleActor { fail_init: false },
+            bounded_channel(10),
+        );
+
+        actor_ref.tell(Fail).await.unwrap();
+
+        assert!(matches!(actor_ref.join().await, Ok(ExitReason::Stopped)));
+    }
 }
@@ -0,0 +1,507 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::supervisor::SupervisorError,
+    handler::ActorMessageHandlerTrait,
+    log,
+    messaging::{ActorShared, MessageEnumTrait, Receiver},
+    runtime::{SpawnFuture, Spawner},
+    stats,
+    supervision::{ActorFactoryTrait, ActorTrait, ExitReason, run_until_exit},
+    watch,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartStrategy {
+    OneForOne,
+    OneForAll,
+    RestForOne,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RestartIntensity {
+    pub max_restarts: usize,
+    pub window: Duration,
+}
+
+impl Default for RestartIntensity {
+    fn default() -> Self {
+        RestartIntensity {
+            max_restarts: 3,
+            window: Duration::from_secs(5),
+        }
+    }
+}
+
+pub struct Supervisor {
+    strategy: RestartStrategy,
+    intensity: RestartIntensity,
+    children: Vec<Child>,
+}
+
+struct Child {
+    spec: Box<dyn ChildSpecTrait>,
+    shutdown: Option<async_channel::Sender<()>>,
+    finished: bool,
+}
+
+struct ChildExit {
+    index: usize,
+    reason: ExitReason,
+}
+
+impl Supervisor {
+    pub fn new(strategy: RestartStrategy) -> Self {
+        Supervisor {
+            strategy,
+            intensity: RestartIntensity::default(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_intensity(mut self, max_restarts: usize, window: Duration) -> Self {
+        self.intensity = RestartIntensity {
+            max_restarts,
+            window,
+        };
+
+        self
+    }
+
+    pub fn child<F, A, M, E>(mut self, factory: F, rx: Receiver<M>) -> Self
+    where
+        F: ActorFactoryTrait<A> + 'static,
+        M: MessageEnumTrait + Send + 'static,
+        A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+        E: Send + Debug + Display + 'static,
+    {
+        let spec = ChildSpec {
+            factory: SharedFactory(Arc::new(Mutex::new(factory))),
+            rx,
+            _marker: PhantomData,
+        };
+
+        self.children.push(Child {
+            spec: Box::new(spec),
+            shutdown: None,
+            finished: false,
+        });
+
+        self
+    }
+
+    pub async fn run<S>(mut self, spawner: S) -> Result<(), SupervisorError>
+    where
+        S: Spawner,
+    {
+        let (exit_tx, exit_rx) = async_channel::unbounded::<ChildExit>();
+        let mut pending = VecDeque::new();
+        let mut restarts = VecDeque::new();
+
+        for index in 0..self.children.len() {
+            self.start_child(index, &spawner, &exit_tx);
+        }
+
+        while self.children.iter().any(|child| !child.finished) {
+            let exit = match pending.pop_front() {
+                Some(exit) => exit,
+                None => match exit_rx.recv().await {
+                    Ok(exit) => exit,
+                    Err(_) => break,
+                },
+            };
+
+            let child = &mut self.children[exit.index];
+            child.shutdown = None;
+
+            if !matches!(exit.reason, ExitReason::Failed(_)) || child.spec.is_closed() {
+                child.finished = true;
+                child.spec.finish(exit.reason).await;
+
+                continue;
+            }
+
+            let now = Instant::now();
+            restarts
+                .retain(|restarted_at| now.duration_since(*restarted_at) < self.intensity.window);
+
+            if restarts.len() >= self.intensity.max_restarts {
+                let running = (0..self.children.len()).collect::<Vec<_>>();
+                self.stop_children(&running, &exit_rx, &mut pending).await;
+
+                log::error(format!(
+                    "Supervisor gave up: child {} {}",
+                    exit.index, exit.reason
+                ));
+
+                for (index, child) in self.children.iter_mut().enumerate() {
+                    if !child.finished {
+                        let reason = match index == exit.index {
+                            true => exit.reason.clone(),
+                            false => ExitReason::Stopped,
+                        };
+
+                        child.finished = true;
+                        child.spec.finish(reason).await;
+                    }
+                }
+
+                return Err(SupervisorError::IntensityExceeded {
+                    max_restarts: self.intensity.max_restarts,
+                    window: self.intensity.window,
+                });
+            }
+
+            restarts.push_back(now);
+
+            let affected = match self.strategy {
+                RestartStrategy::OneForOne => vec![exit.index],
+                RestartStrategy::OneForAll => (0..self.children.len()).collect(),
+                RestartStrategy::RestForOne => (exit.index..self.children.len()).collect(),
+            };
+
+            self.stop_children(&affected, &exit_rx, &mut pending).await;
+
+            log::info(format!(
+                "Supervisor restarting children {affected:?}: child {} {}",
+                exit.index, exit.reason
+            ));
+
+            for index in affected {
+                if !self.children[index].finished {
+                    stats::record_restart(self.children[index].spec.shared());
+                    self.start_child(index, &spawner, &exit_tx);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn start_child<S>(
+        &mut self,
+        index: usize,
+        spawner: &S,
+        exit_tx: &async_channel::Sender<ChildExit>,
+    ) where
+        S: Spawner,
+    {
+        let (shutdown_tx, shutdown_rx) = async_channel::bounded(1);
+        let future = self.children[index]
+            .spec
+            .start(index, shutdown_rx, exit_tx.clone());
+
+        self.children[index].shutdown = Some(shutdown_tx);
+
+        spawner.spawn(future);
+    }
+
+    async fn stop_children(
+        &mut self,
+        indices: &[usize],
+        exit_rx: &async_channel::Receiver<ChildExit>,
+        pending: &mut VecDeque<ChildExit>,
+    ) {
+        let mut stopping = Vec::new();
+
+        for &index in indices {
+            if let Some(shutdown) = self.children[index].shutdown.take() {
+                shutdown.close();
+                stopping.push(index);
+            }
+        }
+
+        while !stopping.is_empty() {
+            let Ok(exit) = exit_rx.recv().await else {
+                break;
+            };
+
+            match stopping.iter().position(|index| *index == exit.index) {
+                Some(position) => {
+                    stopping.swap_remove(position);
+                }
+                None => pending.push_back(exit),
+            }
+        }
+    }
+}
+
+trait ChildSpecTrait: Send {
+    fn start(
+        &mut self,
+        index: usize,
+        shutdown: async_channel::Receiver<()>,
+        exit_tx: async_channel::Sender<ChildExit>,
+    ) -> SpawnFuture;
+
+    fn is_closed(&self) -> bool;
+
+    fn shared(&self) -> &ActorShared;
+
+    fn finish(&mut self, reason: ExitReason) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+// shared with the running child so an in-place restart builds a fresh actor too
+struct SharedFactory<F>(Arc<Mutex<F>>);
+
+impl<F, A> ActorFactoryTrait<A> for SharedFactory<F>
+where
+    F: ActorFactoryTrait<A>,
+{
+    fn create(&mut self) -> A {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .create()
+    }
+}
+
+impl<F> Clone for SharedFactory<F> {
+    fn clone(&self) -> SharedFactory<F> {
+        SharedFactory(self.0.clone())
+    }
+}
+
+struct ChildSpec<F, A, M, E> {
+    factory: SharedFactory<F>,
+    rx: Receiver<M>,
+    _marker: PhantomData<fn() -> (A, E)>,
+}
+
+impl<F, A, M, E> ChildSpecTrait for ChildSpec<F, A, M, E>
+where
+    F: ActorFactoryTrait<A> + 'static,
+    M: MessageEnumTrait + Send + 'static,
+    A: ActorMessageHandlerTrait<M, E> + ActorTrait<E> + Send + Sync + 'static,
+    E: Send + Debug + Display + 'static,
+{
+    fn start(
+        &mut self,
+        index: usize,
+        shutdown: async_channel::Receiver<()>,
+        exit_tx: async_channel::Sender<ChildExit>,
+    ) -> SpawnFuture {
+        let mut factory = self.factory.clone();
+        let mut actor = factory.create();
+        let rx = self.rx.clone();
+
+        Box::pin(async move {
+            let reason = run_until_exit(&mut actor, &rx, Some(&mut factory), Some(&shutdown)).await;
+
+            let _ = exit_tx.send(ChildExit { index, reason }).await;
+        })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.rx.rx.is_closed()
+    }
+
+    fn shared(&self) -> &ActorShared {
+        &self.rx.shared
+    }
+
+    fn finish(&mut self, reason: ExitReason) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        self.rx.close();
+
+        Box::pin(watch::terminate(&self.rx.shared, reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+
+    use crate::{
+        bounded_channel,
+        error::{handler::DefaultHandlerError, supervisor::SupervisorError},
+        handler::{AskHandlerTrait, TellHandlerTrait},
+        messaging::Sender,
+        runtime::SpawnFuture,
+        supervision::{ActorTrait, CommandMessage},
+        supervisor::{RestartStrategy, Supervisor},
+    };
+
+    struct ChildActor;
+
+    pub struct Crash;
+    pub struct Ping;
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("child crashed")]
+    struct ChildCrashedError;
+
+    crate::match_messages! {
+        actor: ChildActor;
+        error: DefaultHandlerError;
+
+        ChildActorMessage {
+            Crash;
+            Ping -> ();
+        }
+    }
+
+    impl ActorTrait<DefaultHandlerError> for ChildActor {}
+
+    #[async_trait]
+    impl TellHandlerTrait<Crash, DefaultHandlerError> for ChildActor {
+        async fn handle(&mut self, _msg: Crash) -> Result<(), DefaultHandlerError> {
+            Err(ChildCrashedError.into())
+        }
+    }
+
+    #[async_trait]
+    impl AskHandlerTrait<Ping, (), DefaultHandlerError> for ChildActor {
+        async fn handle(&mut self, _msg: Ping) -> Result<(), DefaultHandlerError> {
+            Ok(())
+        }
+    }
+
+    type SupervisorHandle = tokio::task::JoinHandle<Result<(), SupervisorError>>;
+
+    fn run_supervisor(
+        supervisor: Supervisor,
+        children: usize,
+    ) -> (
+        Vec<Sender<ChildActorMessage>>,
+        Vec<Arc<AtomicUsize>>,
+        SupervisorHandle,
+    ) {
+        let mut supervisor = supervisor;
+        let mut senders = Vec::new();
+        let mut starts = Vec::new();
+
+        for _ in 0..children {
+            let (tx, rx) = bounded_channel(10);
+            let started = Arc::new(AtomicUsize::new(0));
+            let counter = started.clone();
+
+            supervisor = supervisor.child(
+                move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+
+                    ChildActor
+                },
+                rx,
+            );
+
+            senders.push(tx);
+            starts.push(started);
+        }
+
+        let handle = tokio::spawn(supervisor.run(|future: SpawnFuture| {
+            tokio::spawn(future);
+        }));
+
+        (senders, starts, handle)
+    }
+
+    fn started(starts: &[Arc<AtomicUsize>]) -> Vec<usize> {
+        starts
+            .iter()
+            .map(|started| started.load(Ordering::SeqCst))
+            .collect()
+    }
+
+    // the queued ping is answered by the restarted actor once every affected child is back
+    async fn crash(senders: &[Sender<ChildActorMessage>], index: usize) {
+        senders[index].tell(Crash).await.unwrap();
+        senders[index].ask(Ping).await.unwrap();
+    }
+
+    async fn stop_all(senders: &[Sender<ChildActorMessage>], handle: SupervisorHandle) {
+        for sender in senders {
+            sender.command(CommandMessage::StopActor).await.unwrap();
+        }
+
+        assert!(handle.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn one_for_one_restarts_failed_child() {
+        let (senders, starts, handle) =
+            run_supervisor(Supervisor::new(RestartStrategy::OneForOne), 3);
+
+        crash(&senders, 1).await;
+
+        assert_eq!(started(&starts), [1, 2, 1]);
+
+        stop_all(&senders, handle).await;
+    }
+
+    #[tokio::test]
+    async fn one_for_all_restarts_every_child() {
+        let (senders, starts, handle) =
+            run_supervisor(Supervisor::new(RestartStrategy::OneForAll), 3);
+
+        crash(&senders, 1).await;
+
+        assert_eq!(started(&starts), [2, 2, 2]);
+
+        stop_all(&senders, handle).await;
+    }
+
+    #[tokio::test]
+    async fn rest_for_one_restarts_later_children() {
+        let (senders, starts, handle) =
+            run_supervisor(Supervisor::new(RestartStrategy::RestForOne), 3);
+
+        crash(&senders, 1).await;
+
+        assert_eq!(started(&starts), [1, 2, 2]);
+
+        stop_all(&senders, handle).await;
+    }
+
+    #[tokio::test]
+    async fn intensity_limit_stops_supervisor() {
+        let supervisor =
+            Supervisor::new(RestartStrategy::OneForOne).with_intensity(1, Duration::from_secs(5));
+        let (senders, starts, handle) = run_supervisor(supervisor, 2);
+
+        crash(&senders, 0).await;
+        senders[0].tell(Crash).await.unwrap();
+
+        assert!(matches!(
+            handle.await.unwrap(),
+            Err(SupervisorError::IntensityExceeded {
+                max_restarts: 1,
+                ..
+            })
+        ));
+        assert_eq!(started(&starts), [2, 1]);
+        assert!(senders[1].ask(Ping).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn stop_command_ends_child_for_good() {
+        let (senders, starts, handle) =
+            run_supervisor(Supervisor::new(RestartStrategy::OneForAll), 2);
+
+        senders[0].command(CommandMessage::StopActor).await.unwrap();
+
+        assert!(senders[0].ask(Ping).await.is_err());
+        senders[1].ask(Ping).await.unwrap();
+        assert_eq!(started(&starts), [1, 1]);
+
+        senders[1].command(CommandMessage::StopActor).await.unwrap();
+
+        assert!(handle.await.unwrap().is_ok());
+        assert_eq!(started(&starts), [1, 1]);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, OnceLock, Weak},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+static TIMER: OnceLock<Arc<Timer>> = OnceLock::new();
+
+// keyed by deadline and a sequence number so a dropped sleep can remove its own entry
+type TimerKey = (Instant, u64);
+
+struct Timer {
+    entries: Mutex<TimerEntries>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct TimerEntries {
+    wakers: BTreeMap<TimerKey, Weak<Mutex<Waker>>>,
+    next_id: u64,
+}
+
+fn timer() -> &'static Timer {
+    TIMER.get_or_init(|| {
+        let timer = Arc::new(Timer {
+            entries: Mutex::new(TimerEntries::default()),
+            condvar: Condvar::new(),
+        });
+
+        let worker = timer.clone();
+        thread::Builder::new()
+            .name("ascolt-timer".to_string())
+            .spawn(move || worker.run())
+            .expect("failed to spawn timer thread");
+
+        timer
+    })
+}
+
+impl Timer {
+    fn register(&self, deadline: Instant, waker: Weak<Mutex<Waker>>) -> TimerKey {
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        let is_earliest = entries
+            .wakers
+            .first_key_value()
+            .is_none_or(|((earliest, _), _)| deadline < *earliest);
+
+        let key = (deadline, entries.next_id);
+        entries.next_id += 1;
+        entries.wakers.insert(key, waker);
+
+        if is_earliest {
+            self.condvar.notify_one();
+        }
+
+        key
+    }
+
+    fn cancel(&self, key: &TimerKey) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .wakers
+            .remove(key);
+    }
+
+    fn run(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+
+        loop {
+            let now = Instant::now();
+
+            while entries
+                .wakers
+                .first_key_value()
+                .is_some_and(|((deadline, _), _)| *deadline <= now)
+            {
+                if let Some(waker) = entries
+                    .wakers
+                    .pop_first()
+                    .and_then(|(_, waker)| waker.upgrade())
+                {
+                    waker
+                        .lock()
+                        .unwrap_or_else(|err| err.into_inner())
+                        .wake_by_ref();
+                }
+            }
+
+            entries = match entries.wakers.first_key_value() {
+                Some(((deadline, _), _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+
+                    self.condvar
+                        .wait_timeout(entries, timeout)
+                        .unwrap_or_else(|err| err.into_inner())
+                        .0
+                }
+                None => self
+                    .condvar
+                    .wait(entries)
+                    .unwrap_or_else(|err| err.into_inner()),
+            };
+        }
+    }
+}
+
+pub struct Sleep {
+    deadline: Instant,
+    waker: Option<(Arc<Mutex<Waker>>, TimerKey)>,
+}
+
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(Instant::now() + duration)
+}
+
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep {
+        deadline,
+        waker: None,
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        match &self.waker {
+            Some((waker, _)) => {
+                let mut waker = waker.lock().unwrap_or_else(|err| err.into_inner());
+
+                if !waker.will_wake(cx.waker()) {
+                    waker.clone_from(cx.waker());
+                }
+            }
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                let key = timer().register(self.deadline, Arc::downgrade(&waker));
+
+                self.waker = Some((waker, key));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Sleep {
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some((_, key)) = &self.waker {
+            timer().cancel(key);
+        }
+    }
+}
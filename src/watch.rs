@@ -0,0 +1,266 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc, MutexGuard, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    log,
+    messaging::{ActorShared, MessageRequest, TellMessage, WeakSender},
+    supervision::{CommandMessage, ExitReason},
+};
+
+static NEXT_ACTOR_ID: AtomicU64 = AtomicU64::new(1);
+
+type BoxFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActorId(u64);
+
+impl ActorId {
+    pub(crate) fn next() -> Self {
+        ActorId(NEXT_ACTOR_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for ActorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "actor-{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Terminated {
+    pub id: ActorId,
+    pub reason: ExitReason,
+}
+
+pub struct WatchHandle {
+    shared: Weak<ActorShared>,
+    watch_id: u64,
+}
+
+impl WatchHandle {
+    pub fn unwatch(self) {
+        if let Some(shared) = self.shared.upgrade() {
+            lock(&shared)
+                .watchers
+                .retain(|(watch_id, _)| *watch_id != self.watch_id);
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Lifecycle {
+    exit_reason: Option<ExitReason>,
+    next_watch_id: u64,
+    watchers: Vec<(u64, Box<dyn WatcherTrait>)>,
+    links: Vec<(ActorId, Box<dyn LinkTrait>)>,
+    parent: Option<Weak<ActorShared>>,
+}
+
+trait WatcherTrait: Send + Sync {
+    fn notify(&self, terminated: Terminated) -> BoxFuture<'_>;
+
+    fn is_closed(&self) -> bool;
+}
+
+impl<W> WatcherTrait for WeakSender<W>
+where
+    W: Send + Sync + 'static,
+    TellMessage<Terminated>: MessageRequest<W>,
+{
+    fn notify(&self, terminated: Terminated) -> BoxFuture<'_> {
+        Box::pin(async move {
+            if let Some(watcher) = self.upgrade() {
+                let _ = watcher.tell(terminated).await;
+            }
+        })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.upgrade().is_none_or(|watcher| watcher.is_closed())
+    }
+}
+
+trait LinkTrait: Send + Sync {
+    fn stop(&self) -> BoxFuture<'_>;
+
+    fn shared(&self) -> Option<Arc<ActorShared>>;
+}
+
+impl<M> LinkTrait for WeakSender<M>
+where
+    M: Send + Sync + 'static,
+{
+    fn stop(&self) -> BoxFuture<'_> {
+        Box::pin(async move {
+            if let Some(linked) = self.upgrade() {
+                let _ = linked.command(CommandMessage::StopActor).await;
+            }
+        })
+    }
+
+    fn shared(&self) -> Option<Arc<ActorShared>> {
+        WeakSender::shared(self)
+    }
+}
+
+fn lock(shared: &ActorShared) -> MutexGuard<'_, Lifecycle> {
+    shared
+        .lifecycle
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+pub(crate) async fn watch<W>(shared: &Arc<ActorShared>, watcher: WeakSender<W>) -> WatchHandle
+where
+    W: Send + Sync + 'static,
+    TellMessage<Terminated>: MessageRequest<W>,
+{
+    let (watch_id, exit_reason) = {
+        let mut lifecycle = lock(shared);
+        let watch_id = lifecycle.next_watch_id;
+        lifecycle.next_watch_id += 1;
+
+        let exit_reason = lifecycle.exit_reason.clone();
+
+        if exit_reason.is_none() {
+            lifecycle
+                .watchers
+                .retain(|(_, watcher)| !watcher.is_closed());
+            lifecycle
+                .watchers
+                .push((watch_id, Box::new(watcher.clone())));
+        }
+
+        (watch_id, exit_reason)
+    };
+
+    if let Some(reason) = exit_reason {
+        let terminated = Terminated {
+            id: shared.id,
+            reason,
+        };
+
+        watcher.notify(terminated).await;
+    }
+
+    WatchHandle {
+        shared: Arc::downgrade(shared),
+        watch_id,
+    }
+}
+
+pub(crate) async fn link<M, N>(first: WeakSender<M>, second: WeakSender<N>)
+where
+    M: Send + Sync + 'static,
+    N: Send + Sync + 'static,
+{
+    let (Some(first_shared), Some(second_shared)) = (first.shared(), second.shared()) else {
+        return;
+    };
+
+    if first_shared.id == second_shared.id {
+        return;
+    }
+
+    let first_terminated = add_link(&first_shared, second_shared.id, second.clone());
+    let second_terminated = add_link(&second_shared, first_shared.id, first.clone());
+
+    if first_terminated {
+        remove_link(&second_shared, first_shared.id);
+        second.stop().await;
+    }
+
+    if second_terminated {
+        remove_link(&first_shared, second_shared.id);
+        first.stop().await;
+    }
+}
+
+pub(crate) fn unlink(first: &ActorShared, second: &ActorShared) {
+    remove_link(first, second.id);
+    remove_link(second, first.id);
+}
+
+// children are stopped with their parent, a terminated child only leaves the parent's links
+pub(crate) fn add_child<M>(parent: &Arc<ActorShared>, child: WeakSender<M>)
+where
+    M: Send + Sync + 'static,
+{
+    let Some(child_shared) = child.shared() else {
+        return;
+    };
+
+    lock(&child_shared).parent = Some(Arc::downgrade(parent));
+
+    if add_link(parent, child_shared.id, child) {
+        let _ = child_shared.try_command(CommandMessage::StopActor);
+    }
+}
+
+fn add_link<M>(shared: &ActorShared, id: ActorId, linked: WeakSender<M>) -> bool
+where
+    M: Send + Sync + 'static,
+{
+    let mut lifecycle = lock(shared);
+
+    if lifecycle.exit_reason.is_some() {
+        return true;
+    }
+
+    if !lifecycle
+        .links
+        .iter()
+        .any(|(linked_id, _)| *linked_id == id)
+    {
+        lifecycle.links.push((id, Box::new(linked)));
+    }
+
+    false
+}
+
+fn remove_link(shared: &ActorShared, id: ActorId) {
+    lock(shared).links.retain(|(linked_id, _)| *linked_id != id);
+}
+
+pub(crate) async fn terminate(shared: &ActorShared, reason: ExitReason) {
+    let (watchers, links, parent) = {
+        let mut lifecycle = lock(shared);
+        lifecycle.exit_reason = Some(reason.clone());
+
+        (
+            std::mem::take(&mut lifecycle.watchers),
+            std::mem::take(&mut lifecycle.links),
+            lifecycle.parent.take(),
+        )
+    };
+
+    if let Some(parent) = parent.and_then(|parent| parent.upgrade()) {
+        remove_link(&parent, shared.id);
+    }
+
+    for (_, watcher) in watchers {
+        let terminated = Terminated {
+            id: shared.id,
+            reason: reason.clone(),
+        };
+
+        watcher.notify(terminated).await;
+    }
+
+    for (linked_id, linked) in links {
+        if let Some(linked_shared) = linked.shared() {
+            remove_link(&linked_shared, shared.id);
+        }
+
+        log::info(format!("Stopping {linked_id} linked to {}", shared.id));
+
+        linked.stop().await;
+    }
+}